@@ -0,0 +1,88 @@
+//! Publish serialized slabs from a span stage to an async queue producer.
+//!
+//! The span stage writes into a `SlabSink`. `accept` serializes each slab to
+//! a JSON payload and buffers it, and `flush` hands the batch to an async
+//! publisher task over a tokio channel. The publisher awaits each send to the
+//! "topic", the way a Kafka, NATS, or SQS client awaits its producer future,
+//! and a separate async task plays the embedding service that consumes the
+//! topic.
+//!
+//! `SlabSink` is synchronous so the crate does not pick an async runtime. The
+//! span stage therefore runs on a blocking thread and uses `blocking_send`
+//! to cross into the runtime. The bounded channels give backpressure in both
+//! directions.
+//!
+//! Run with: `cargo run --example slab_sink --features serde`
+
+use slabs::{slabs_from_byte_ranges, Error, Result, Slab, SlabSink};
+use tokio::sync::mpsc;
+
+/// A `SlabSink` that publishes JSON-encoded slabs in batches through an
+/// async publisher task.
+struct AsyncJsonProducer {
+    batches: mpsc::Sender<Vec<Vec<u8>>>,
+    pending: Vec<Vec<u8>>,
+}
+
+impl SlabSink for AsyncJsonProducer {
+    fn accept(&mut self, slab: Slab) -> Result<()> {
+        let payload = serde_json::to_vec(&slab).map_err(|err| Error::Sink(err.to_string()))?;
+        self.pending.push(payload);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let batch = std::mem::take(&mut self.pending);
+        self.batches
+            .blocking_send(batch)
+            .map_err(|_| Error::Sink("publisher stopped".into()))
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let document = "Ada designed the engine. She wrote notes. Babbage built it.";
+    let ranges = [0..24, 25..41, 42..59];
+
+    let (batches, mut batch_queue) = mpsc::channel::<Vec<Vec<u8>>>(1);
+    let (topic, mut subscription) = mpsc::channel::<Vec<u8>>(2);
+
+    // The queue client: awaits every publish.
+    let publisher = tokio::spawn(async move {
+        while let Some(batch) = batch_queue.recv().await {
+            for payload in batch {
+                topic.send(payload).await.expect("consumer is alive");
+            }
+        }
+    });
+
+    // The embedding service only sees bytes on the wire.
+    let consumer = tokio::spawn(async move {
+        while let Some(payload) = subscription.recv().await {
+            let slab: Slab = serde_json::from_slice(&payload).expect("valid payload");
+            println!(
+                "embed slab {} {:?} ({} bytes on the wire): {:?}",
+                slab.index,
+                slab.span(),
+                payload.len(),
+                slab.text
+            );
+        }
+    });
+
+    // The span stage is synchronous, so it runs off the async threads.
+    tokio::task::spawn_blocking(move || {
+        let mut sink = AsyncJsonProducer {
+            batches,
+            pending: Vec::new(),
+        };
+        let spans = slabs_from_byte_ranges(document, &ranges).expect("valid ranges");
+        sink.accept_all(spans)
+    })
+    .await
+    .expect("span stage")
+    .expect("publisher is alive");
+
+    publisher.await.expect("publisher task");
+    consumer.await.expect("consumer task");
+}
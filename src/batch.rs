@@ -0,0 +1,170 @@
+//! Ordering and grouping slabs for embedding batches.
+
+use std::collections::VecDeque;
+
+/// Round-robin iterator over several per-document sequences.
+///
+/// Created by [`interleave`]. Yields `(document, item)` pairs.
+#[derive(Debug)]
+pub struct Interleave<I> {
+    queue: VecDeque<(usize, I)>,
+}
+
+/// Interleave per-document slab sequences in round-robin order.
+///
+/// Yields the first slab of every document, then the second of every document
+/// that has one, and so on, tagged with the document's position in the input.
+/// Feeding this into fixed-size embedding batches mixes short and long
+/// documents instead of filling whole batches from one large document.
+///
+/// Documents are consumed lazily; exhausted documents drop out of the
+/// rotation.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{interleave, Slab};
+///
+/// let a = vec![Slab::new("a0", 0, 2, 0), Slab::new("a1", 2, 4, 1), Slab::new("a2", 4, 6, 2)];
+/// let b = vec![Slab::new("b0", 0, 2, 0)];
+///
+/// let order: Vec<(usize, String)> = interleave([a, b])
+///     .map(|(doc, slab)| (doc, slab.text))
+///     .collect();
+///
+/// assert_eq!(
+///     order,
+///     [(0, "a0".into()), (1, "b0".into()), (0, "a1".into()), (0, "a2".into())]
+/// );
+/// ```
+pub fn interleave<D>(documents: D) -> Interleave<<D::Item as IntoIterator>::IntoIter>
+where
+    D: IntoIterator,
+    D::Item: IntoIterator,
+{
+    Interleave {
+        queue: documents
+            .into_iter()
+            .map(IntoIterator::into_iter)
+            .enumerate()
+            .collect(),
+    }
+}
+
+impl<I: Iterator> Iterator for Interleave<I> {
+    type Item = (usize, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((doc, mut iter)) = self.queue.pop_front() {
+            if let Some(item) = iter.next() {
+                self.queue.push_back((doc, iter));
+                return Some((doc, item));
+            }
+        }
+        None
+    }
+}
+
+/// Group items into batches of similar length.
+///
+/// Transformer embedders pad every sequence in a batch to the longest one, so
+/// batches that mix 20-token and 500-token slabs waste most of their compute.
+/// This assigns each item to a length bucket and cuts each bucket into batches
+/// of at most `max_batch` items.
+///
+/// `len` measures an item, typically its token count (or `Slab::len` as a
+/// byte proxy). `bucket_bounds` are upper bounds, exclusive: with bounds
+/// `[64, 256]`, buckets hold lengths `< 64`, `64..256`, and `>= 256`. Bounds
+/// may be given in any order. A `max_batch` of zero is treated as one.
+///
+/// Returns batches of indices into `items`, ordered by bucket and then by
+/// position, so embeddings can be written back to the right slabs.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{length_batches, Slab};
+///
+/// let slabs = vec![
+///     Slab::new("a long slab of text", 0, 19, 0),
+///     Slab::new("short", 20, 25, 1),
+///     Slab::new("tiny", 26, 30, 2),
+///     Slab::new("another long slab!", 31, 49, 3),
+/// ];
+///
+/// let batches = length_batches(&slabs, Slab::len, &[10], 8);
+/// assert_eq!(batches, [vec![1, 2], vec![0, 3]]);
+/// ```
+pub fn length_batches<T, F>(
+    items: &[T],
+    len: F,
+    bucket_bounds: &[usize],
+    max_batch: usize,
+) -> Vec<Vec<usize>>
+where
+    F: Fn(&T) -> usize,
+{
+    let mut bounds = bucket_bounds.to_vec();
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); bounds.len() + 1];
+    for (i, item) in items.iter().enumerate() {
+        let bucket = bounds.partition_point(|&bound| bound <= len(item));
+        buckets[bucket].push(i);
+    }
+
+    let max_batch = max_batch.max(1);
+    buckets
+        .iter()
+        .flat_map(|bucket| bucket.chunks(max_batch).map(<[usize]>::to_vec))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_documents_are_skipped() {
+        let docs: Vec<Vec<u8>> = vec![vec![], vec![1, 2], vec![], vec![3]];
+
+        let order: Vec<(usize, u8)> = interleave(docs).collect();
+
+        assert_eq!(order, [(1, 1), (3, 3), (1, 2)]);
+    }
+
+    #[test]
+    fn every_item_is_yielded_once() {
+        let docs: Vec<Vec<usize>> = (0..5).map(|d| (0..d * 3).collect()).collect();
+
+        let total = interleave(docs).count();
+
+        assert_eq!(total, 3 + 6 + 9 + 12);
+    }
+
+    #[test]
+    fn bucket_bounds_are_exclusive_upper_limits() {
+        let lengths = [63usize, 64, 255, 256, 0];
+
+        let batches = length_batches(&lengths, |&n| n, &[256, 64], 10);
+
+        assert_eq!(batches, [vec![0, 4], vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn large_buckets_split_at_max_batch() {
+        let lengths = [1usize; 5];
+
+        let batches = length_batches(&lengths, |&n| n, &[], 2);
+
+        assert_eq!(batches, [vec![0, 1], vec![2, 3], vec![4]]);
+    }
+
+    #[test]
+    fn zero_max_batch_means_singletons() {
+        let batches = length_batches(&[1usize, 2], |&n| n, &[], 0);
+
+        assert_eq!(batches, [vec![0], vec![1]]);
+    }
+}
@@ -0,0 +1,218 @@
+//! Idempotent upserts and deletes between slab runs.
+
+use std::collections::HashSet;
+
+use crate::{Slab, SlabId, SlabIdStrategy};
+
+/// Metadata key holding the document version a slab was cut from, written by
+/// [`attach_version`].
+pub const DOC_VERSION_KEY: &str = "doc_version";
+/// Metadata key holding when the slab run happened, written by
+/// [`attach_version`].
+pub const CHUNKED_AT_KEY: &str = "chunked_at";
+
+/// One slab to upsert, keyed for idempotent writes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlabRecord {
+    /// The document the slab belongs to.
+    pub doc_id: String,
+    /// The slab's [`Slab::id`] under the chosen strategy.
+    pub id: SlabId,
+    /// The slab's [`Slab::content_hash`].
+    pub content_hash: SlabId,
+    /// Position of the slab in the new slab slice.
+    pub position: usize,
+}
+
+/// What a downstream index must apply to move from one run to the next.
+///
+/// Applying `upserts` and deleting `tombstones` is idempotent: replaying the
+/// same change set, or one computed from the same two runs, leaves the index
+/// in the same state.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlabChanges {
+    /// New-run slabs whose id the previous run did not emit.
+    pub upserts: Vec<SlabRecord>,
+    /// Previous-run ids the new run no longer emits, in old slab order.
+    pub tombstones: Vec<SlabId>,
+}
+
+/// Compute upserts and tombstones between two runs over one document.
+///
+/// Ids come from [`Slab::id`] with `doc_id` and `strategy`. A new slab is
+/// upserted when its id was not in the old run, and an old id is tombstoned
+/// when the new run does not produce it. This covers slabs that
+/// [`diff_slabs`](crate::diff_slabs) reports as deleted. It also covers
+/// modified or shifted slabs whose id changed under `strategy`, which
+/// `SlabDiff::deleted` alone would leave behind as stale entries.
+/// Duplicate ids (repeated text under [`SlabIdStrategy::Content`]) are
+/// emitted once.
+///
+/// Compare each upsert's `content_hash` with the stored one to decide
+/// whether it needs a fresh embedding.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{slab_changes, slabs_from_byte_ranges, SlabIdStrategy};
+///
+/// let old_text = "One. Two. Three.";
+/// let new_text = "One. Three.";
+/// let old = slabs_from_byte_ranges(old_text, &[0..4, 5..9, 10..16]).unwrap();
+/// let new = slabs_from_byte_ranges(new_text, &[0..4, 5..11]).unwrap();
+///
+/// let changes = slab_changes("doc-1", &old, &new, SlabIdStrategy::Content);
+///
+/// assert!(changes.upserts.is_empty());
+/// assert_eq!(changes.tombstones, [old[1].id("doc-1", SlabIdStrategy::Content)]);
+/// ```
+#[must_use]
+pub fn slab_changes(
+    doc_id: &str,
+    old: &[Slab],
+    new: &[Slab],
+    strategy: SlabIdStrategy,
+) -> SlabChanges {
+    let old_ids: HashSet<SlabId> = old.iter().map(|slab| slab.id(doc_id, strategy)).collect();
+    let mut new_ids = HashSet::with_capacity(new.len());
+
+    let mut upserts = Vec::new();
+    for (position, slab) in new.iter().enumerate() {
+        let id = slab.id(doc_id, strategy);
+        if new_ids.insert(id) && !old_ids.contains(&id) {
+            upserts.push(SlabRecord {
+                doc_id: doc_id.to_string(),
+                id,
+                content_hash: slab.content_hash(),
+                position,
+            });
+        }
+    }
+
+    let mut seen = HashSet::with_capacity(old.len());
+    let tombstones = old
+        .iter()
+        .map(|slab| slab.id(doc_id, strategy))
+        .filter(|id| !new_ids.contains(id) && seen.insert(*id))
+        .collect();
+
+    SlabChanges {
+        upserts,
+        tombstones,
+    }
+}
+
+/// Stamp every slab with the document version and run time.
+///
+/// Values are stored under [`DOC_VERSION_KEY`] and [`CHUNKED_AT_KEY`] as
+/// given. slabs has no clock or date type, so pass `chunked_at` in whatever
+/// form the index sorts on, such as an RFC 3339 timestamp or Unix seconds.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{attach_version, slabs_from_byte_ranges, CHUNKED_AT_KEY, DOC_VERSION_KEY};
+///
+/// let text = "One. Two.";
+/// let mut slabs = slabs_from_byte_ranges(text, &[0..4, 5..9]).unwrap();
+/// attach_version(&mut slabs, "v7", "2026-10-15T12:00:00Z");
+///
+/// assert_eq!(slabs[1].metadata[DOC_VERSION_KEY], "v7");
+/// assert_eq!(slabs[1].metadata[CHUNKED_AT_KEY], "2026-10-15T12:00:00Z");
+/// ```
+pub fn attach_version(slabs: &mut [Slab], doc_version: &str, chunked_at: &str) {
+    for slab in slabs {
+        slab.metadata
+            .insert(DOC_VERSION_KEY.to_string(), doc_version.to_string());
+        slab.metadata
+            .insert(CHUNKED_AT_KEY.to_string(), chunked_at.to_string());
+    }
+}
+
+/// Ids emitted for `old` that a run producing `new` supersedes.
+///
+/// These are the [`SlabChanges::tombstones`] of [`slab_changes`]: old ids the
+/// new version no longer produces. A freshness-aware index can expire or
+/// down-rank them instead of deleting outright.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{slabs_from_byte_ranges, superseded_ids, SlabIdStrategy};
+///
+/// let old = slabs_from_byte_ranges("Price: 10.", &[0..10]).unwrap();
+/// let new = slabs_from_byte_ranges("Price: 12.", &[0..10]).unwrap();
+///
+/// let stale = superseded_ids("doc", &old, &new, SlabIdStrategy::Content);
+/// assert_eq!(stale, [old[0].id("doc", SlabIdStrategy::Content)]);
+/// ```
+#[must_use]
+pub fn superseded_ids(
+    doc_id: &str,
+    old: &[Slab],
+    new: &[Slab],
+    strategy: SlabIdStrategy,
+) -> Vec<SlabId> {
+    slab_changes(doc_id, old, new, strategy).tombstones
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slabs_from_byte_ranges;
+
+    #[test]
+    fn shifted_slabs_are_replaced_under_offset_ids() {
+        let old_text = "One. Three.";
+        let new_text = "Zero. One. Three.";
+        let old = slabs_from_byte_ranges(old_text, &[0..4, 5..11]).unwrap();
+        let new = slabs_from_byte_ranges(new_text, &[0..5, 6..10, 11..17]).unwrap();
+
+        let changes = slab_changes("d", &old, &new, SlabIdStrategy::OffsetsAndContent);
+
+        assert_eq!(
+            changes
+                .upserts
+                .iter()
+                .map(|r| r.position)
+                .collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+        assert_eq!(changes.tombstones.len(), 2);
+        assert_eq!(changes.upserts[1].content_hash, old[0].content_hash());
+    }
+
+    #[test]
+    fn identical_runs_change_nothing() {
+        let text = "a. b.";
+        let slabs = slabs_from_byte_ranges(text, &[0..2, 3..5]).unwrap();
+
+        let changes = slab_changes("d", &slabs, &slabs, SlabIdStrategy::default());
+
+        assert_eq!(changes, SlabChanges::default());
+    }
+
+    #[test]
+    fn repeated_content_ids_are_emitted_once() {
+        let text = "hi hi";
+        let new = slabs_from_byte_ranges(text, &[0..2, 3..5]).unwrap();
+
+        let changes = slab_changes("d", &[], &new, SlabIdStrategy::Content);
+
+        assert_eq!(changes.upserts.len(), 1);
+        assert_eq!(changes.upserts[0].doc_id, "d");
+    }
+
+    #[test]
+    fn version_stamps_do_not_change_ids() {
+        let text = "a. b.";
+        let old = slabs_from_byte_ranges(text, &[0..2, 3..5]).unwrap();
+        let mut new = old.clone();
+        attach_version(&mut new, "2", "1760000000");
+
+        assert!(superseded_ids("d", &old, &new, SlabIdStrategy::default()).is_empty());
+        assert_eq!(new[0].metadata[DOC_VERSION_KEY], "2");
+    }
+}
@@ -0,0 +1,93 @@
+//! Human-readable citations for slabs.
+
+use crate::Slab;
+
+/// Metadata key holding the source document name used by [`Slab::citation`].
+pub const SOURCE_KEY: &str = "source";
+/// Metadata key holding the page label used by [`Slab::citation`].
+pub const PAGE_KEY: &str = "page";
+/// Metadata key holding the section label used by [`Slab::citation`].
+pub const SECTION_KEY: &str = "section";
+
+impl Slab {
+    /// Render a citation for this slab from its metadata and offsets.
+    ///
+    /// Parts are joined with `", "` in this order, each only when present:
+    /// the [`SOURCE_KEY`] value, `p.` and the [`PAGE_KEY`] value, `§` and the
+    /// [`SECTION_KEY`] value, then the character span (or the byte span when
+    /// character offsets are not set). Span numbers use comma thousands
+    /// separators and an en dash, with an exclusive end as everywhere else in
+    /// the crate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use slabs::{Slab, PAGE_KEY, SECTION_KEY, SOURCE_KEY};
+    ///
+    /// let slab = Slab::new("Revenue grew.", 10_400, 10_413, 3)
+    ///     .with_char_offsets(10_342, 10_355)
+    ///     .with_metadata(SOURCE_KEY, "quarterly-report.pdf")
+    ///     .with_metadata(PAGE_KEY, "12")
+    ///     .with_metadata(SECTION_KEY, "2.3");
+    ///
+    /// assert_eq!(
+    ///     slab.citation(),
+    ///     "quarterly-report.pdf, p. 12, §2.3, chars 10,342–10,355"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn citation(&self) -> String {
+        let mut parts = Vec::with_capacity(4);
+        if let Some(source) = self.metadata.get(SOURCE_KEY) {
+            parts.push(source.clone());
+        }
+        if let Some(page) = self.metadata.get(PAGE_KEY) {
+            parts.push(format!("p. {page}"));
+        }
+        if let Some(section) = self.metadata.get(SECTION_KEY) {
+            parts.push(format!("§{section}"));
+        }
+        let (unit, span) = match self.char_span() {
+            Some(span) => ("chars", span),
+            None => ("bytes", self.span()),
+        };
+        parts.push(format!(
+            "{unit} {}–{}",
+            thousands(span.start),
+            thousands(span.end)
+        ));
+        parts.join(", ")
+    }
+}
+
+fn thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thousands_groups_from_the_right() {
+        assert_eq!(thousands(0), "0");
+        assert_eq!(thousands(999), "999");
+        assert_eq!(thousands(1_000), "1,000");
+        assert_eq!(thousands(1_234_567), "1,234,567");
+    }
+
+    #[test]
+    fn bare_slab_cites_its_byte_span() {
+        let slab = Slab::new("x", 1_500, 1_501, 0);
+
+        assert_eq!(slab.citation(), "bytes 1,500–1,501");
+    }
+}
@@ -0,0 +1,138 @@
+//! Inspection summaries for individual slabs.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+
+use crate::{Slab, TOKEN_END_KEY, TOKEN_START_KEY};
+
+/// A summary of one slab for debugging views and admin consoles.
+///
+/// Built by [`Slab::describe`]. Boundary provenance such as the strategy or
+/// heading path is whatever the boundary source stored in metadata; slabs
+/// does not track it separately.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlabDescription {
+    /// Position of the slab in its sequence.
+    pub index: usize,
+    /// Byte span in the source.
+    pub bytes: Range<usize>,
+    /// Character span in the source, if computed.
+    pub chars: Option<Range<usize>>,
+    /// Length in bytes.
+    pub byte_len: usize,
+    /// Length in characters.
+    pub char_len: usize,
+    /// Token count, when the slab carries token window metadata.
+    pub tokens: Option<usize>,
+    /// Unique byte span, if computed.
+    pub core: Option<Range<usize>>,
+    /// Leading bytes shared with earlier slabs, from the core span.
+    pub overlap_before: usize,
+    /// Trailing bytes outside the core span.
+    pub overlap_after: usize,
+    /// The slab's metadata.
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Slab {
+    /// Summarize this slab's position, size, overlap, and metadata.
+    ///
+    /// Overlap counts come from the core span set by
+    /// [`compute_core_spans`](crate::compute_core_spans) and are zero without
+    /// one. The token count is read from [`TOKEN_START_KEY`] and
+    /// [`TOKEN_END_KEY`]. `Display` renders one `field: value` line per field.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use slabs::{compute_core_spans, slabs_from_byte_ranges};
+    ///
+    /// let text = "The quick brown fox";
+    /// let mut slabs = slabs_from_byte_ranges(text, &[0..11, 8..19]).unwrap();
+    /// compute_core_spans(&mut slabs);
+    ///
+    /// let description = slabs[1].describe();
+    /// assert_eq!(description.byte_len, 11);
+    /// assert_eq!(description.overlap_before, 3);
+    /// assert!(description.to_string().contains("bytes: 8..19"));
+    /// ```
+    #[must_use]
+    pub fn describe(&self) -> SlabDescription {
+        let core = self.core_span();
+        let (overlap_before, overlap_after) = match &core {
+            Some(core) => (
+                core.start.saturating_sub(self.start),
+                self.end.saturating_sub(core.end),
+            ),
+            None => (0, 0),
+        };
+        let token_index = |key: &str| self.metadata.get(key)?.parse::<usize>().ok();
+        let tokens = match (token_index(TOKEN_START_KEY), token_index(TOKEN_END_KEY)) {
+            (Some(start), Some(end)) => end.checked_sub(start),
+            _ => None,
+        };
+
+        SlabDescription {
+            index: self.index,
+            bytes: self.span(),
+            chars: self.char_span(),
+            byte_len: self.len(),
+            char_len: self.char_len(),
+            tokens,
+            core,
+            overlap_before,
+            overlap_after,
+            metadata: self.metadata.clone(),
+        }
+    }
+}
+
+impl fmt::Display for SlabDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "index: {}", self.index)?;
+        writeln!(f, "bytes: {:?}", self.bytes)?;
+        if let Some(chars) = &self.chars {
+            writeln!(f, "chars: {chars:?}")?;
+        }
+        writeln!(f, "byte_len: {}", self.byte_len)?;
+        writeln!(f, "char_len: {}", self.char_len)?;
+        if let Some(tokens) = self.tokens {
+            writeln!(f, "tokens: {tokens}")?;
+        }
+        if let Some(core) = &self.core {
+            writeln!(f, "core: {core:?}")?;
+        }
+        writeln!(f, "overlap_before: {}", self.overlap_before)?;
+        write!(f, "overlap_after: {}", self.overlap_after)?;
+        for (key, value) in &self.metadata {
+            write!(f, "\n{key}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::slabs_from_token_windows;
+
+    #[test]
+    fn token_windows_report_their_token_count() {
+        let slabs = slabs_from_token_windows("a b c", &[(0, 1), (2, 3), (4, 5)], 2, 2).unwrap();
+
+        assert_eq!(slabs[0].describe().tokens, Some(2));
+        assert_eq!(slabs[1].describe().tokens, Some(1));
+    }
+
+    #[test]
+    fn display_lists_metadata_last() {
+        let slab = crate::Slab::new("é", 0, 2, 0).with_metadata("heading", "Intro");
+
+        assert_eq!(
+            slab.describe().to_string(),
+            "index: 0\nbytes: 0..2\nbyte_len: 2\nchar_len: 1\n\
+             overlap_before: 0\noverlap_after: 0\nheading: Intro"
+        );
+    }
+}
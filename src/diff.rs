@@ -0,0 +1,246 @@
+//! Slab-level diffs between two versions of a document.
+
+use std::ops::Range;
+
+use crate::Slab;
+
+/// How the slabs of a new document version relate to the previous version.
+///
+/// Old positions index the previous slab slice; new positions index the new
+/// one. Every position appears in exactly one list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlabDiff {
+    /// `(old, new)` pairs with identical text at corresponding offsets.
+    /// Embeddings for these can be reused.
+    pub unchanged: Vec<(usize, usize)>,
+    /// `(old, new)` pairs that cover the same region of the document but
+    /// whose text or boundaries changed. Re-embed the new slab and replace the
+    /// old one.
+    pub modified: Vec<(usize, usize)>,
+    /// New slabs with no counterpart in the old version.
+    pub added: Vec<usize>,
+    /// Old slabs with no counterpart in the new version.
+    pub deleted: Vec<usize>,
+}
+
+impl SlabDiff {
+    /// Whether the new version needs no re-embedding.
+    #[must_use]
+    pub fn is_unchanged(&self) -> bool {
+        self.modified.is_empty() && self.added.is_empty() && self.deleted.is_empty()
+    }
+}
+
+/// Compare slabs of two document versions.
+///
+/// The boundary source runs on both versions as usual; `diff_slabs` then
+/// tells the indexing layer which embeddings to keep, replace, add, and delete.
+///
+/// The edit between `old_text` and `new_text` is located as a single region
+/// between their common prefix and common suffix. An old slab is unchanged
+/// when a new slab has the same text at either the same span or the span
+/// shifted by the document's length change. Remaining old slabs are mapped
+/// into the new document (endpoints inside the edited region stretch to cover
+/// it) and paired as modified with the new slab they overlap most. Anything
+/// left over is added or deleted.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{diff_slabs, slabs_from_byte_ranges};
+///
+/// let old_text = "One. Two. Three.";
+/// let new_text = "One. Two! Three.";
+/// let old = slabs_from_byte_ranges(old_text, &[0..4, 5..9, 10..16]).unwrap();
+/// let new = slabs_from_byte_ranges(new_text, &[0..4, 5..9, 10..16]).unwrap();
+///
+/// let diff = diff_slabs(old_text, &old, new_text, &new);
+/// assert_eq!(diff.unchanged, [(0, 0), (2, 2)]);
+/// assert_eq!(diff.modified, [(1, 1)]);
+/// ```
+#[must_use]
+pub fn diff_slabs(old_text: &str, old: &[Slab], new_text: &str, new: &[Slab]) -> SlabDiff {
+    let edit = Edit::between(old_text, new_text);
+    let mapped: Vec<Range<usize>> = old.iter().map(|slab| edit.map(slab.span())).collect();
+
+    let mut old_used = vec![false; old.len()];
+    let mut new_used = vec![false; new.len()];
+    let mut diff = SlabDiff::default();
+
+    for (o, slab) in old.iter().enumerate() {
+        let shifted = edit.shift(slab.span());
+        let hit = new.iter().enumerate().find(|(n, candidate)| {
+            !new_used[*n]
+                && candidate.text == slab.text
+                && (candidate.span() == slab.span() || Some(candidate.span()) == shifted)
+        });
+        if let Some((n, _)) = hit {
+            old_used[o] = true;
+            new_used[n] = true;
+            diff.unchanged.push((o, n));
+        }
+    }
+
+    for o in 0..old.len() {
+        if old_used[o] {
+            continue;
+        }
+        let best = new
+            .iter()
+            .enumerate()
+            .filter(|(n, _)| !new_used[*n])
+            .map(|(n, candidate)| (n, overlap(&mapped[o], &candidate.span())))
+            .filter(|(_, len)| *len > 0)
+            .max_by_key(|(n, len)| (*len, std::cmp::Reverse(*n)));
+        if let Some((n, _)) = best {
+            old_used[o] = true;
+            new_used[n] = true;
+            diff.modified.push((o, n));
+        }
+    }
+
+    diff.deleted = (0..old.len()).filter(|&o| !old_used[o]).collect();
+    diff.added = (0..new.len()).filter(|&n| !new_used[n]).collect();
+    diff.unchanged.sort_unstable();
+    diff.modified.sort_unstable();
+    diff
+}
+
+/// The single edited region between two strings.
+struct Edit {
+    /// Length of the common prefix.
+    prefix: usize,
+    /// End of the edited region in the old string.
+    old_end: usize,
+    /// End of the edited region in the new string.
+    new_end: usize,
+}
+
+impl Edit {
+    fn between(old: &str, new: &str) -> Self {
+        let (a, b) = (old.as_bytes(), new.as_bytes());
+
+        let mut prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+        while !old.is_char_boundary(prefix) || !new.is_char_boundary(prefix) {
+            prefix -= 1;
+        }
+
+        let max_suffix = a.len().min(b.len()) - prefix;
+        let mut suffix = a
+            .iter()
+            .rev()
+            .zip(b.iter().rev())
+            .take(max_suffix)
+            .take_while(|(x, y)| x == y)
+            .count();
+        while !old.is_char_boundary(a.len() - suffix) || !new.is_char_boundary(b.len() - suffix) {
+            suffix -= 1;
+        }
+
+        Self {
+            prefix,
+            old_end: a.len() - suffix,
+            new_end: b.len() - suffix,
+        }
+    }
+
+    /// Shift an old span by the length change, if it stays in bounds.
+    fn shift(&self, span: Range<usize>) -> Option<Range<usize>> {
+        let start = (span.start + self.new_end).checked_sub(self.old_end)?;
+        let end = (span.end + self.new_end).checked_sub(self.old_end)?;
+        Some(start..end)
+    }
+
+    /// Map an old span into new-string coordinates. Endpoints inside the
+    /// edited region stretch to cover the new edited region.
+    fn map(&self, span: Range<usize>) -> Range<usize> {
+        let map_point = |x: usize, is_end: bool| {
+            if x <= self.prefix {
+                x
+            } else if x >= self.old_end {
+                x - self.old_end + self.new_end
+            } else if is_end {
+                self.new_end
+            } else {
+                self.prefix
+            }
+        };
+        map_point(span.start, false)..map_point(span.end, true)
+    }
+}
+
+fn overlap(a: &Range<usize>, b: &Range<usize>) -> usize {
+    a.end.min(b.end).saturating_sub(a.start.max(b.start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slabs_from_byte_ranges;
+
+    #[test]
+    fn identical_documents_are_unchanged() {
+        let text = "One. Two.";
+        let slabs = slabs_from_byte_ranges(text, &[0..4, 5..9]).unwrap();
+
+        let diff = diff_slabs(text, &slabs, text, &slabs);
+
+        assert!(diff.is_unchanged());
+        assert_eq!(diff.unchanged, [(0, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn slabs_after_an_insertion_shift_and_stay_unchanged() {
+        let old_text = "One. Three.";
+        let new_text = "One. Two. Three.";
+        let old = slabs_from_byte_ranges(old_text, &[0..4, 5..11]).unwrap();
+        let new = slabs_from_byte_ranges(new_text, &[0..4, 5..9, 10..16]).unwrap();
+
+        let diff = diff_slabs(old_text, &old, new_text, &new);
+
+        assert_eq!(diff.unchanged, [(0, 0), (1, 2)]);
+        assert_eq!(diff.added, [1]);
+        assert!(diff.modified.is_empty() && diff.deleted.is_empty());
+    }
+
+    #[test]
+    fn removed_trailing_slab_is_deleted() {
+        let old_text = "One. Two.";
+        let new_text = "One.";
+        let old = slabs_from_byte_ranges(old_text, &[0..4, 5..9]).unwrap();
+        let new = vec![Slab::from_byte_range(new_text, 0..4, 0).unwrap()];
+
+        let diff = diff_slabs(old_text, &old, new_text, &new);
+
+        assert_eq!(diff.unchanged, [(0, 0)]);
+        assert_eq!(diff.deleted, [1]);
+    }
+
+    #[test]
+    fn rechunked_region_pairs_by_largest_overlap() {
+        let old_text = "aaaa bbbb cccc";
+        let new_text = "aaaa bxbb cccc";
+        let old = slabs_from_byte_ranges(old_text, &[0..9, 10..14]).unwrap();
+        let new = slabs_from_byte_ranges(new_text, &[0..4, 5..9, 10..14]).unwrap();
+
+        let diff = diff_slabs(old_text, &old, new_text, &new);
+
+        assert_eq!(diff.unchanged, [(1, 2)]);
+        assert_eq!(diff.modified, [(0, 0)]);
+        assert_eq!(diff.added, [1]);
+    }
+
+    #[test]
+    fn multibyte_edit_keeps_char_boundaries() {
+        let old_text = "é. b.";
+        let new_text = "è. b.";
+        let old = slabs_from_byte_ranges(old_text, &[0..3, 4..6]).unwrap();
+        let new = slabs_from_byte_ranges(new_text, &[0..3, 4..6]).unwrap();
+
+        let diff = diff_slabs(old_text, &old, new_text, &new);
+
+        assert_eq!(diff.unchanged, [(1, 1)]);
+        assert_eq!(diff.modified, [(0, 0)]);
+    }
+}
@@ -32,8 +32,35 @@ pub enum Error {
         offset: usize,
     },
 
-    /// Compatibility error for adapters that map upstream embedding failures
-    /// into `slabs::Error`.
+    /// A window size or stride was zero.
+    #[error("invalid window: size {window}, stride {stride} (both must be non-zero)")]
+    InvalidWindow {
+        /// Requested window size.
+        window: usize,
+        /// Requested stride.
+        stride: usize,
+    },
+
+    /// A [`SlabTemplate`](crate::SlabTemplate) string was malformed.
+    #[error("invalid template at byte {offset}: {reason}")]
+    InvalidTemplate {
+        /// Byte offset of the offending brace in the template string.
+        offset: usize,
+        /// What was wrong.
+        reason: &'static str,
+    },
+
+    /// A [`SlabSink`](crate::SlabSink) could not accept a slab.
+    #[error("sink error: {0}")]
+    Sink(String),
+
+    /// A [`SlabTransform`](crate::SlabTransform) or a fallible
+    /// [`SlabSource`](crate::SlabSource) adapter could not process its input.
+    #[error("transform error: {0}")]
+    Transform(String),
+
+    /// An upstream embedding failure, reported by a
+    /// [`TokenEmbedder`](crate::TokenEmbedder) or another adapter.
     #[error("embedding error: {0}")]
     Embedding(String),
 }
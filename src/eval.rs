@@ -0,0 +1,100 @@
+//! Span-level measurements for tuning boundary sources.
+
+use std::ops::Range;
+
+use crate::Slab;
+
+/// How answer spans fall across a slab set.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Fragmentation {
+    /// Answer spans considered (empty spans are skipped).
+    pub answers: usize,
+    /// Answers fully contained in at least one slab.
+    pub contained: usize,
+    /// Answers overlapping slabs but contained in none.
+    pub fragmented: usize,
+    /// Answers overlapping no slab at all.
+    pub uncovered: usize,
+}
+
+impl Fragmentation {
+    /// Fraction of answers that no single slab contains, in `0.0..=1.0`.
+    ///
+    /// Returns `0.0` when there are no answers.
+    #[must_use]
+    pub fn rate(&self) -> f64 {
+        if self.answers == 0 {
+            return 0.0;
+        }
+        (self.fragmented + self.uncovered) as f64 / self.answers as f64
+    }
+}
+
+/// Measure how often answer spans cross slab boundaries.
+///
+/// An answer that no single slab contains cannot be retrieved whole by one
+/// hit. Comparing this rate across overlap settings gives an empirical basis
+/// for choosing overlap, using answer byte spans from a QA set over the same
+/// source string as `slabs`.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{answer_fragmentation, slabs_from_byte_ranges};
+///
+/// let text = "Ada designed the engine. Babbage built it.";
+/// let slabs = slabs_from_byte_ranges(text, &[0..24, 25..42]).unwrap();
+///
+/// // "the engine" sits in slab 0; "engine. Babbage" crosses the boundary.
+/// let stats = answer_fragmentation(&slabs, &[13..23, 17..32]);
+///
+/// assert_eq!(stats.contained, 1);
+/// assert_eq!(stats.fragmented, 1);
+/// assert_eq!(stats.rate(), 0.5);
+/// ```
+#[must_use]
+pub fn answer_fragmentation(slabs: &[Slab], answers: &[Range<usize>]) -> Fragmentation {
+    let mut stats = Fragmentation::default();
+    for answer in answers.iter().filter(|a| a.start < a.end) {
+        stats.answers += 1;
+        if slabs
+            .iter()
+            .any(|slab| slab.start <= answer.start && answer.end <= slab.end)
+        {
+            stats.contained += 1;
+        } else if slabs
+            .iter()
+            .any(|slab| slab.start < answer.end && answer.start < slab.end)
+        {
+            stats.fragmented += 1;
+        } else {
+            stats.uncovered += 1;
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlap_recovers_boundary_answers() {
+        let answer = [8..12, 0..4];
+        let disjoint = vec![Slab::new("", 0, 10, 0), Slab::new("", 10, 20, 1)];
+        let overlapping = vec![Slab::new("", 0, 10, 0), Slab::new("", 6, 20, 1)];
+
+        assert_eq!(answer_fragmentation(&disjoint, &answer).rate(), 0.5);
+        assert_eq!(answer_fragmentation(&overlapping, &answer).rate(), 0.0);
+    }
+
+    #[test]
+    fn answers_in_gaps_are_uncovered() {
+        let slabs = vec![Slab::new("", 0, 5, 0), Slab::new("", 10, 15, 1)];
+
+        let stats = answer_fragmentation(&slabs, &[6..9, 3..3]);
+
+        assert_eq!(stats.answers, 1);
+        assert_eq!(stats.uncovered, 1);
+    }
+}
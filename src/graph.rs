@@ -0,0 +1,171 @@
+//! Relationships between slabs from the same source string.
+
+use crate::Slab;
+
+/// The relationship an edge records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SlabEdgeKind {
+    /// `to` follows `from` in sequence order.
+    Next,
+    /// `from` is the smallest slab whose byte span strictly contains `to`.
+    Contains,
+    /// `from` links to an anchor inside `to`. See
+    /// [`reference_edges`](crate::reference_edges).
+    Reference,
+}
+
+/// A directed edge between two slabs.
+///
+/// `from` and `to` are positions in the slice passed to [`slab_edges`], not
+/// [`Slab::index`] values, so the edge list is valid even when several
+/// independent sequences share one slice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlabEdge {
+    /// Source slab position.
+    pub from: usize,
+    /// Target slab position.
+    pub to: usize,
+    /// Relationship kind.
+    pub kind: SlabEdgeKind,
+}
+
+/// Build adjacency and containment edges for slabs from one source string.
+///
+/// - [`SlabEdgeKind::Next`] links each slab to the next one with a greater
+///   [`Slab::index`] in the same nesting level. For flat sequences this is
+///   plain document order.
+/// - [`SlabEdgeKind::Contains`] links each slab to its innermost strict
+///   container, if any. Parent/child structure (sections holding paragraphs,
+///   paragraphs holding sentences) falls out of the byte spans.
+///
+/// Containment is found by comparing every pair, so cost is quadratic in
+/// `slabs.len()`. Build graphs per document.
+///
+/// With the `serde` feature, edges serialize directly for graph stores or
+/// JSON export.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{slab_edges, Slab, SlabEdge, SlabEdgeKind};
+///
+/// let slabs = vec![
+///     Slab::new("A. B.", 0, 5, 0),
+///     Slab::new("A.", 0, 2, 1),
+///     Slab::new("B.", 3, 5, 2),
+/// ];
+/// let edges = slab_edges(&slabs);
+///
+/// assert!(edges.contains(&SlabEdge { from: 0, to: 1, kind: SlabEdgeKind::Contains }));
+/// assert!(edges.contains(&SlabEdge { from: 1, to: 2, kind: SlabEdgeKind::Next }));
+/// ```
+#[must_use]
+pub fn slab_edges(slabs: &[Slab]) -> Vec<SlabEdge> {
+    let parents: Vec<Option<usize>> = (0..slabs.len())
+        .map(|child| innermost_container(slabs, child))
+        .collect();
+
+    let mut edges = Vec::new();
+    for (child, parent) in parents.iter().enumerate() {
+        if let Some(parent) = *parent {
+            edges.push(SlabEdge {
+                from: parent,
+                to: child,
+                kind: SlabEdgeKind::Contains,
+            });
+        }
+    }
+
+    // Siblings share a parent; order them by index and link neighbours.
+    let mut levels: Vec<(Option<usize>, usize, usize)> = parents
+        .iter()
+        .enumerate()
+        .map(|(pos, parent)| (*parent, slabs[pos].index, pos))
+        .collect();
+    levels.sort_unstable();
+    for pair in levels.windows(2) {
+        let (parent_a, _, from) = pair[0];
+        let (parent_b, _, to) = pair[1];
+        if parent_a == parent_b {
+            edges.push(SlabEdge {
+                from,
+                to,
+                kind: SlabEdgeKind::Next,
+            });
+        }
+    }
+
+    edges
+}
+
+fn innermost_container(slabs: &[Slab], child: usize) -> Option<usize> {
+    let inner = &slabs[child];
+    slabs
+        .iter()
+        .enumerate()
+        .filter(|(pos, outer)| {
+            *pos != child
+                && outer.start <= inner.start
+                && outer.end >= inner.end
+                && outer.span() != inner.span()
+        })
+        .min_by_key(|(pos, outer)| (outer.end - outer.start, *pos))
+        .map(|(pos, _)| pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges_of(kind: SlabEdgeKind, edges: &[SlabEdge]) -> Vec<(usize, usize)> {
+        edges
+            .iter()
+            .filter(|edge| edge.kind == kind)
+            .map(|edge| (edge.from, edge.to))
+            .collect()
+    }
+
+    #[test]
+    fn flat_sequence_links_neighbours_in_index_order() {
+        let slabs = vec![
+            Slab::new("c", 4, 5, 2),
+            Slab::new("a", 0, 1, 0),
+            Slab::new("b", 2, 3, 1),
+        ];
+
+        let edges = slab_edges(&slabs);
+
+        assert_eq!(edges_of(SlabEdgeKind::Next, &edges), [(1, 2), (2, 0)]);
+        assert!(edges_of(SlabEdgeKind::Contains, &edges).is_empty());
+    }
+
+    #[test]
+    fn nested_spans_link_only_to_innermost_parent() {
+        let slabs = vec![
+            Slab::new("section", 0, 20, 0),
+            Slab::new("para", 0, 10, 1),
+            Slab::new("sent", 0, 4, 2),
+            Slab::new("para", 10, 20, 3),
+        ];
+
+        let edges = slab_edges(&slabs);
+
+        assert_eq!(
+            edges_of(SlabEdgeKind::Contains, &edges),
+            [(0, 1), (1, 2), (0, 3)]
+        );
+        assert_eq!(edges_of(SlabEdgeKind::Next, &edges), [(1, 3)]);
+    }
+
+    #[test]
+    fn identical_spans_do_not_contain_each_other() {
+        let slabs = vec![Slab::new("x", 0, 1, 0), Slab::new("x", 0, 1, 1)];
+
+        let edges = slab_edges(&slabs);
+
+        assert!(edges_of(SlabEdgeKind::Contains, &edges).is_empty());
+        assert_eq!(edges_of(SlabEdgeKind::Next, &edges), [(0, 1)]);
+    }
+}
@@ -0,0 +1,218 @@
+//! Stable slab identifiers.
+
+use std::fmt;
+
+use crate::Slab;
+
+/// Which slab fields feed a [`SlabId`].
+///
+/// Every strategy also hashes the document id, so equal slabs in different
+/// documents get different ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SlabIdStrategy {
+    /// Hash the text only. The id survives edits elsewhere in the document
+    /// that shift this slab's offsets. Repeated text within one document
+    /// produces repeated ids.
+    Content,
+    /// Hash the byte span only. The id changes whenever the slab moves and
+    /// stays the same when its text is edited in place.
+    Offsets,
+    /// Hash both the byte span and the text. The id changes when either does.
+    #[default]
+    OffsetsAndContent,
+}
+
+/// A stable 128-bit slab identifier.
+///
+/// Ids are computed with FNV-1a over a length-prefixed encoding of the
+/// selected fields. The encoding and hash are fixed, so ids are identical
+/// across runs, platforms, and crate versions. They are not cryptographic.
+///
+/// `Display` renders 32 lowercase hex digits, which fits UUID-shaped id
+/// columns in most vector stores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SlabId(u128);
+
+impl SlabId {
+    /// The raw 128-bit value.
+    #[must_use]
+    pub fn as_u128(self) -> u128 {
+        self.0
+    }
+}
+
+impl fmt::Display for SlabId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+/// Serialized as its 32-digit hex [`Display`](fmt::Display) form.
+#[cfg(feature = "serde")]
+impl serde::Serialize for SlabId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SlabId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        u128::from_str_radix(&hex, 16)
+            .map(SlabId)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl Slab {
+    /// Compute a stable id for this slab within the document `doc_id`.
+    ///
+    /// Re-running the same boundary source over unchanged text yields the same
+    /// ids, so vector store upserts keyed on them stay idempotent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use slabs::{Slab, SlabIdStrategy};
+    ///
+    /// let before = Slab::new("world", 7, 12, 1);
+    /// let after = Slab::new("world", 9, 14, 1);
+    ///
+    /// assert_eq!(
+    ///     before.id("doc-1", SlabIdStrategy::Content),
+    ///     after.id("doc-1", SlabIdStrategy::Content),
+    /// );
+    /// assert_ne!(
+    ///     before.id("doc-1", SlabIdStrategy::OffsetsAndContent),
+    ///     after.id("doc-1", SlabIdStrategy::OffsetsAndContent),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn id(&self, doc_id: &str, strategy: SlabIdStrategy) -> SlabId {
+        let mut hasher = Fnv1a128::new();
+        hasher.write(&[strategy as u8]);
+        hasher.write_field(doc_id.as_bytes());
+        if matches!(
+            strategy,
+            SlabIdStrategy::Offsets | SlabIdStrategy::OffsetsAndContent
+        ) {
+            hasher.write(&(self.start as u64).to_le_bytes());
+            hasher.write(&(self.end as u64).to_le_bytes());
+        }
+        if matches!(
+            strategy,
+            SlabIdStrategy::Content | SlabIdStrategy::OffsetsAndContent
+        ) {
+            hasher.write_field(self.text.as_bytes());
+        }
+        SlabId(hasher.finish())
+    }
+}
+
+impl Slab {
+    /// Hash this slab's text alone, independent of document and position.
+    ///
+    /// Equal text gives an equal hash in any document, so the hash can key an
+    /// embedding cache: a slab whose [`id`](Slab::id) changed but whose
+    /// content hash did not can reuse its embedding.
+    #[must_use]
+    pub fn content_hash(&self) -> SlabId {
+        let mut hasher = Fnv1a128::new();
+        hasher.write_field(self.text.as_bytes());
+        SlabId(hasher.finish())
+    }
+}
+
+/// 128-bit FNV-1a. Small, dependency-free, and fixed across Rust releases,
+/// unlike `std::collections::hash_map::DefaultHasher`.
+struct Fnv1a128(u128);
+
+impl Fnv1a128 {
+    const OFFSET_BASIS: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+    const PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u128::from(byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Write a length prefix, then the bytes, so field boundaries are
+    /// unambiguous.
+    fn write_field(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    fn finish(&self) -> u128 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv1a_matches_reference_vectors() {
+        let mut empty = Fnv1a128::new();
+        empty.write(b"");
+        assert_eq!(empty.finish(), 0x6c62272e07bb014262b821756295c58d);
+
+        let mut a = Fnv1a128::new();
+        a.write(b"a");
+        assert_eq!(a.finish(), 0xd228cb696f1a8caf78912b704e4a8964);
+    }
+
+    #[test]
+    fn ids_are_pinned_across_releases() {
+        let slab = Slab::new("world", 7, 12, 0);
+
+        // Changing this value breaks every stored id downstream.
+        assert_eq!(
+            slab.id("doc", SlabIdStrategy::OffsetsAndContent)
+                .to_string(),
+            PINNED_ID
+        );
+    }
+
+    #[test]
+    fn document_id_separates_equal_slabs() {
+        let slab = Slab::new("same", 0, 4, 0);
+
+        assert_ne!(
+            slab.id("a", SlabIdStrategy::Content),
+            slab.id("b", SlabIdStrategy::Content)
+        );
+    }
+
+    #[test]
+    fn field_boundaries_are_unambiguous() {
+        let left = Slab::new("bc", 0, 2, 0);
+        let right = Slab::new("c", 0, 1, 0);
+
+        assert_ne!(
+            left.id("a", SlabIdStrategy::Content),
+            right.id("ab", SlabIdStrategy::Content)
+        );
+    }
+
+    #[test]
+    fn offsets_strategy_ignores_text() {
+        let before = Slab::new("old", 3, 6, 0);
+        let after = Slab::new("new", 3, 6, 0);
+
+        assert_eq!(
+            before.id("doc", SlabIdStrategy::Offsets),
+            after.id("doc", SlabIdStrategy::Offsets)
+        );
+    }
+
+    const PINNED_ID: &str = "4ec939725521eabb007207be3c112caa";
+}
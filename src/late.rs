@@ -61,7 +61,8 @@
 //! Günther, Billerbeck, et al. (2024). "Late Chunking: Contextual Chunk
 //! Embeddings Using Long-Context Embedding Models." arXiv:2409.04701.
 
-use crate::Slab;
+use crate::math::normalized_mean;
+use crate::{Result, Slab, SlabSource};
 
 /// Pools token embeddings into span embeddings.
 ///
@@ -84,6 +85,28 @@ pub struct SpanPooler {
 )]
 pub type LateChunkingPooler = SpanPooler;
 
+/// Token-level output of a full-document encoder.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenEmbeddings {
+    /// One vector per token, in token order.
+    pub vectors: Vec<Vec<f32>>,
+    /// Byte span of each token in the embedded text, parallel to `vectors`.
+    pub offsets: Vec<(usize, usize)>,
+}
+
+/// An encoder that embeds a whole document into per-token vectors.
+///
+/// Implement this around a long-context model (ONNX Runtime, candle, a remote
+/// service) to let [`SpanPooler::slab_and_pool`] run the full late-chunking
+/// pipeline in one call. Map backend failures to
+/// [`Error::Embedding`](crate::Error::Embedding).
+pub trait TokenEmbedder {
+    /// Embed `text` as one sequence.
+    ///
+    /// Offsets must be byte spans into `text`.
+    fn embed_tokens(&self, text: &str) -> Result<TokenEmbeddings>;
+}
+
 impl SpanPooler {
     /// Create a new span pooler.
     ///
@@ -243,78 +266,163 @@ impl SpanPooler {
             .collect()
     }
 
-    /// Mean pool a slice of token embeddings.
-    fn mean_pool(&self, embeddings: &[Vec<f32>]) -> Vec<f32> {
-        if embeddings.is_empty() {
-            return vec![0.0; self.dim];
-        }
+    /// Choose slabs with `source`, embed `text` with `embedder`, and pool one
+    /// vector per slab.
+    ///
+    /// This runs boundary selection, full-document embedding, and
+    /// [`pool_with_offsets`](SpanPooler::pool_with_offsets) in sequence.
+    /// Slabs come from [`SlabSource::try_slabs`], so they carry character
+    /// offsets.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from `source` or `embedder`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use slabs::{Chunker, Result, Slab, SpanPooler, TokenEmbedder, TokenEmbeddings};
+    ///
+    /// struct Sentences;
+    ///
+    /// impl Chunker for Sentences {
+    ///     fn chunk_bytes(&self, text: &str) -> Vec<Slab> {
+    ///         vec![Slab::new(&text[..6], 0, 6, 0), Slab::new(&text[7..], 7, 13, 1)]
+    ///     }
+    /// }
+    ///
+    /// /// One token per byte, embedded by byte parity.
+    /// struct Parity;
+    ///
+    /// impl TokenEmbedder for Parity {
+    ///     fn embed_tokens(&self, text: &str) -> Result<TokenEmbeddings> {
+    ///         let bytes = 0..text.len();
+    ///         Ok(TokenEmbeddings {
+    ///             vectors: bytes.clone().map(|i| vec![(i % 2) as f32, 1.0]).collect(),
+    ///             offsets: bytes.map(|i| (i, i + 1)).collect(),
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let (slabs, vectors) = SpanPooler::new(2)
+    ///     .slab_and_pool(&Sentences, &Parity, "Alpha. Beta.!")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(slabs.len(), vectors.len());
+    /// assert_eq!(slabs[1].char_span(), Some(7..13));
+    /// ```
+    pub fn slab_and_pool<S, E>(
+        &self,
+        source: &S,
+        embedder: &E,
+        text: &str,
+    ) -> Result<(Vec<Slab>, Vec<Vec<f32>>)>
+    where
+        S: SlabSource + ?Sized,
+        E: TokenEmbedder + ?Sized,
+    {
+        let slabs = source.try_slabs(text)?;
+        let tokens = embedder.embed_tokens(text)?;
+        let vectors = self.pool_with_offsets(&tokens.vectors, &tokens.offsets, &slabs);
+        Ok((slabs, vectors))
+    }
 
-        let mut result = vec![0.0; self.dim];
-        let count = embeddings.len() as f32;
+    /// Pool slabs from token embeddings of overlapping document windows.
+    ///
+    /// Long-context encoders still have a context limit. For longer
+    /// documents, embed overlapping macro windows separately and pass their
+    /// outputs here in document order, with every offset shifted into
+    /// document byte coordinates. [`slabs_from_token_windows`] can plan the
+    /// windows from the document's token offsets.
+    ///
+    /// Where two windows overlap, tokens starting before the overlap midpoint
+    /// are taken from the earlier window and the rest from the later one, so
+    /// each token comes from the window that gives it more context on both
+    /// sides. The kept tokens are then pooled as in
+    /// [`pool_with_offsets`](SpanPooler::pool_with_offsets), so slabs can
+    /// cross window boundaries.
+    ///
+    /// [`slabs_from_token_windows`]: crate::slabs_from_token_windows
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use slabs::{Slab, SpanPooler, TokenEmbeddings};
+    ///
+    /// // Four one-byte tokens, embedded as two windows overlapping on 1..3.
+    /// let first = TokenEmbeddings {
+    ///     vectors: vec![vec![1.0, 0.0]; 3],
+    ///     offsets: vec![(0, 1), (1, 2), (2, 3)],
+    /// };
+    /// let second = TokenEmbeddings {
+    ///     vectors: vec![vec![0.0, 1.0]; 3],
+    ///     offsets: vec![(1, 2), (2, 3), (3, 4)],
+    /// };
+    /// let slabs = [Slab::new("ab", 0, 2, 0), Slab::new("cd", 2, 4, 1)];
+    ///
+    /// let pooled = SpanPooler::new(2).pool_windowed(&[first, second], &slabs);
+    /// assert_eq!(pooled, [vec![1.0, 0.0], vec![0.0, 1.0]]);
+    /// ```
+    pub fn pool_windowed(&self, windows: &[TokenEmbeddings], chunks: &[Slab]) -> Vec<Vec<f32>> {
+        let windows: Vec<&TokenEmbeddings> = windows
+            .iter()
+            .filter(|window| !window.offsets.is_empty())
+            .collect();
+        // Token starts at or after `cuts[w]` belong to window `w` or later.
+        let mut cuts = Vec::with_capacity(windows.len());
+        let mut previous_end: Option<usize> = None;
+        for window in &windows {
+            let start = window.offsets.iter().map(|&(start, _)| start).min();
+            let end = window.offsets.iter().map(|&(_, end)| end).max();
+            let (start, end) = (start.unwrap_or_default(), end.unwrap_or_default());
+            cuts.push(match previous_end {
+                Some(previous_end) if previous_end > start => start + (previous_end - start) / 2,
+                _ => start,
+            });
+            previous_end = Some(previous_end.map_or(end, |previous| previous.max(end)));
+        }
 
-        for emb in embeddings {
-            debug_assert_eq!(
-                emb.len(),
-                self.dim,
-                "token embedding dimension mismatch: expected {}, got {}",
-                self.dim,
-                emb.len()
+        let mut tokens: Vec<(&[f32], (usize, usize))> = Vec::new();
+        for (w, window) in windows.iter().enumerate() {
+            let upper = cuts.get(w + 1).copied().unwrap_or(usize::MAX);
+            tokens.extend(
+                window
+                    .vectors
+                    .iter()
+                    .zip(&window.offsets)
+                    .filter(|(_, &(start, _))| start >= cuts[w] && start < upper)
+                    .map(|(vector, &offset)| (vector.as_slice(), offset)),
             );
-            for (i, &v) in emb.iter().take(self.dim).enumerate() {
-                result[i] += v;
-            }
         }
 
-        for v in &mut result {
-            *v /= count;
+        if tokens.is_empty() || chunks.is_empty() {
+            return vec![vec![0.0; self.dim]; chunks.len()];
         }
 
-        // L2 normalize.
-        let norm: f32 = result.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if norm > 1e-9 {
-            for v in &mut result {
-                *v /= norm;
-            }
-        }
+        chunks
+            .iter()
+            .map(|chunk| {
+                let mut selected = tokens
+                    .iter()
+                    .filter(|(_, (start, end))| *start < chunk.end && *end > chunk.start)
+                    .map(|&(vector, _)| vector)
+                    .peekable();
+                if selected.peek().is_none() {
+                    return normalized_mean(tokens.iter().map(|&(vector, _)| vector), self.dim);
+                }
+                normalized_mean(selected, self.dim)
+            })
+            .collect()
+    }
 
-        result
+    /// Mean pool a slice of token embeddings.
+    fn mean_pool(&self, embeddings: &[Vec<f32>]) -> Vec<f32> {
+        normalized_mean(embeddings.iter().map(Vec::as_slice), self.dim)
     }
 
     /// Mean pool from references.
     fn mean_pool_refs(&self, embeddings: &[&[f32]]) -> Vec<f32> {
-        if embeddings.is_empty() {
-            return vec![0.0; self.dim];
-        }
-
-        let mut result = vec![0.0; self.dim];
-        let count = embeddings.len() as f32;
-
-        for emb in embeddings {
-            debug_assert_eq!(
-                emb.len(),
-                self.dim,
-                "token embedding dimension mismatch: expected {}, got {}",
-                self.dim,
-                emb.len()
-            );
-            for (i, &v) in emb.iter().take(self.dim).enumerate() {
-                result[i] += v;
-            }
-        }
-
-        for v in &mut result {
-            *v /= count;
-        }
-
-        // L2 normalize.
-        let norm: f32 = result.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if norm > 1e-9 {
-            for v in &mut result {
-                *v /= norm;
-            }
-        }
-
-        result
+        normalized_mean(embeddings.iter().copied(), self.dim)
     }
 }
 
@@ -451,4 +559,66 @@ mod tests {
 
         assert_eq!(pooled[0], vec![1.0, 0.0]);
     }
+
+    #[test]
+    fn slab_and_pool_propagates_embedder_errors() {
+        struct Failing;
+
+        impl TokenEmbedder for Failing {
+            fn embed_tokens(&self, _text: &str) -> Result<TokenEmbeddings> {
+                Err(crate::Error::Embedding("model unavailable".into()))
+            }
+        }
+
+        struct Whole;
+
+        impl crate::Chunker for Whole {
+            fn chunk_bytes(&self, text: &str) -> Vec<Slab> {
+                vec![Slab::new(text, 0, text.len(), 0)]
+            }
+        }
+
+        let err = SpanPooler::new(2)
+            .slab_and_pool(&Whole, &Failing, "text")
+            .unwrap_err();
+
+        assert!(matches!(err, crate::Error::Embedding(_)));
+    }
+
+    #[test]
+    fn windowed_pooling_splits_overlap_at_its_midpoint() {
+        let window = |value: f32, offsets: Vec<(usize, usize)>| TokenEmbeddings {
+            vectors: vec![vec![value, 1.0]; offsets.len()],
+            offsets,
+        };
+        let first = window(0.0, (0..6).map(|i| (i, i + 1)).collect());
+        let second = window(2.0, (2..8).map(|i| (i, i + 1)).collect());
+        // Overlap 2..6 splits at 4: bytes 0..4 from `first`, 4..8 from `second`.
+        let probes = [Slab::new("", 3, 4, 0), Slab::new("", 4, 5, 1)];
+
+        let pooled = SpanPooler::new(2).pool_windowed(&[first, second], &probes);
+
+        assert_eq!(pooled[0], [0.0, 1.0]);
+        assert!(pooled[1][0] > 0.0);
+    }
+
+    #[test]
+    fn windowed_pooling_without_windows_is_zero() {
+        let pooled = SpanPooler::new(2).pool_windowed(&[], &[Slab::new("a", 0, 1, 0)]);
+
+        assert_eq!(pooled, [vec![0.0, 0.0]]);
+    }
+
+    #[test]
+    fn empty_windows_are_skipped() {
+        let window = TokenEmbeddings {
+            vectors: vec![vec![1.0, 0.0]],
+            offsets: vec![(0, 1)],
+        };
+        let windows = [window, TokenEmbeddings::default()];
+
+        let pooled = SpanPooler::new(2).pool_windowed(&windows, &[Slab::new("a", 0, 1, 0)]);
+
+        assert_eq!(pooled, [vec![1.0, 0.0]]);
+    }
 }
@@ -35,7 +35,8 @@
 //! - Embedding generation is upstream; [`SpanPooler`] consumes token
 //!   vectors.
 //! - Storage is downstream; enable the `serde` feature when spans need to cross
-//!   a storage or service boundary.
+//!   a storage or service boundary, and implement [`SlabSink`] around the
+//!   queue or client that carries them.
 //! - Cross-file analysis is out of scope; a slab refers to one source string.
 //!
 //! ## Quick start (retrieval spans)
@@ -63,15 +64,70 @@
 //! let span_embeddings = pooler.pool_with_offsets(&token_embeddings, &token_offsets, &spans);
 //! ```
 
+mod batch;
+mod changes;
+mod cite;
+mod describe;
+mod diff;
 mod error;
+mod eval;
+mod graph;
+mod id;
 mod late;
+mod line;
+mod math;
+mod pack;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod partition;
+mod pipeline;
+mod redact;
+mod refs;
+mod sink;
 mod slab;
+mod snippet;
+pub mod span;
+mod template;
+#[cfg(test)]
+mod test_support;
+mod truncate;
+mod window;
 
+pub use batch::{interleave, length_batches, Interleave};
+pub use changes::{
+    attach_version, slab_changes, superseded_ids, SlabChanges, SlabRecord, CHUNKED_AT_KEY,
+    DOC_VERSION_KEY,
+};
+pub use cite::{PAGE_KEY, SECTION_KEY, SOURCE_KEY};
+pub use describe::SlabDescription;
+pub use diff::{diff_slabs, SlabDiff};
 pub use error::{Error, Result};
+pub use eval::{answer_fragmentation, Fragmentation};
+pub use graph::{slab_edges, SlabEdge, SlabEdgeKind};
+pub use id::{SlabId, SlabIdStrategy};
 #[allow(deprecated)]
 pub use late::LateChunkingPooler;
-pub use late::SpanPooler;
-pub use slab::{compute_char_offsets, slabs_from_byte_ranges, slabs_from_char_ranges, Slab};
+pub use late::{SpanPooler, TokenEmbedder, TokenEmbeddings};
+pub use line::{attach_line_numbers, LineIndex, END_LINE_KEY, START_LINE_KEY};
+pub use pack::{merge_short_slabs, pack_slabs, pack_slabs_balanced, slabs_from_segments};
+#[cfg(feature = "parallel")]
+pub use parallel::slabs_many;
+pub use partition::Partitioned;
+pub use pipeline::{Pipeline, SlabTransform};
+pub use redact::{redact_slabs, RedactionRecord, REDACTION_BYTE};
+pub use refs::{attach_references, reference_edges, REFERENCES_KEY};
+pub use sink::SlabSink;
+pub use slab::{
+    compute_char_offsets, compute_core_spans, slabs_from_byte_ranges, slabs_from_char_ranges, Slab,
+    SlabRef, TrimMode,
+};
+pub use snippet::ELLIPSIS;
+pub use template::SlabTemplate;
+pub use truncate::{truncate_to_bytes, truncate_to_tokens};
+pub use window::{
+    attach_sentence_windows, slab_windows, slabs_from_token_windows, SLAB_END_KEY, SLAB_START_KEY,
+    TOKEN_END_KEY, TOKEN_START_KEY, WINDOW_END_KEY, WINDOW_KEY, WINDOW_START_KEY,
+};
 
 /// A source of already-chosen [`Slab`] boundaries.
 ///
@@ -99,6 +155,48 @@ pub trait SlabSource: Send + Sync {
         slabs
     }
 
+    /// Return slabs with both byte and character offsets, or an error.
+    ///
+    /// The default implementation calls [`slab_bytes`](SlabSource::slab_bytes)
+    /// and checks that every slab span is a valid slice of `text`, returning
+    /// [`Error::InvalidByteSpan`] or [`Error::NonCharBoundary`] for the first
+    /// one that is not, before filling character offsets. Adapters around
+    /// boundary finders that can fail (a parser, a model call) should override
+    /// this to return their real error instead of an empty or degraded result.
+    fn try_slabs(&self, text: &str) -> Result<Vec<Slab>> {
+        let mut slabs = self.slab_bytes(text);
+        slab::validate_slabs(text, &slabs)?;
+        compute_char_offsets(text, &mut slabs);
+        Ok(slabs)
+    }
+
+    /// Return slabs whose text borrows from `text`.
+    ///
+    /// The default implementation calls [`slab_bytes`](SlabSource::slab_bytes)
+    /// and re-borrows each span from `text`, so it still allocates once per
+    /// slab. Implementors that find boundaries without building owned slabs
+    /// should override it to avoid the copies.
+    ///
+    /// Slabs whose offsets are not a valid slice of `text` are skipped. Use
+    /// [`try_slabs`](SlabSource::try_slabs) to get the error instead.
+    fn slab_refs<'a>(&self, text: &'a str) -> Vec<SlabRef<'a>> {
+        slab::borrow_valid_slabs(text, self.slab_bytes(text))
+    }
+
+    /// Return slabs with both byte and character offsets as an iterator.
+    ///
+    /// The default implementation computes byte spans with
+    /// [`slab_bytes`](SlabSource::slab_bytes), then fills character offsets
+    /// lazily as items are pulled. Stopping early skips the remaining offset
+    /// work, and no per-byte lookup table is built. Implementors that find
+    /// boundaries incrementally should override this to yield as they go.
+    fn slabs_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Slab> + 'a
+    where
+        Self: Sized,
+    {
+        slab::with_char_offsets_lazy(text, self.slab_bytes(text))
+    }
+
     /// Estimate the number of slabs for a given text length.
     ///
     /// Useful for pre-allocation. May be approximate.
@@ -139,6 +237,42 @@ pub trait Chunker: Send + Sync {
         slabs
     }
 
+    /// Split text into chunks, or report why chunking failed.
+    ///
+    /// The default implementation calls [`chunk_bytes`](Chunker::chunk_bytes)
+    /// and checks every span against `text` before filling character offsets,
+    /// like [`SlabSource::try_slabs`]. Override it when the underlying chunker
+    /// can fail.
+    fn try_chunk(&self, text: &str) -> Result<Vec<Slab>> {
+        let mut slabs = self.chunk_bytes(text);
+        slab::validate_slabs(text, &slabs)?;
+        compute_char_offsets(text, &mut slabs);
+        Ok(slabs)
+    }
+
+    /// Split text into chunks whose text borrows from `text`.
+    ///
+    /// The default implementation calls [`chunk_bytes`](Chunker::chunk_bytes)
+    /// and re-borrows each span. Override it to skip the per-chunk copies.
+    ///
+    /// Chunks whose offsets are not a valid slice of `text` are skipped. Use
+    /// [`try_chunk`](Chunker::try_chunk) to get the error instead.
+    fn chunk_borrowed<'a>(&self, text: &'a str) -> Vec<SlabRef<'a>> {
+        slab::borrow_valid_slabs(text, self.chunk_bytes(text))
+    }
+
+    /// Split text into chunks lazily.
+    ///
+    /// The default implementation calls [`chunk_bytes`](Chunker::chunk_bytes)
+    /// and fills character offsets as items are pulled. Override it when the
+    /// boundary logic can yield chunks incrementally.
+    fn chunk_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Slab> + 'a
+    where
+        Self: Sized,
+    {
+        slab::with_char_offsets_lazy(text, self.chunk_bytes(text))
+    }
+
     /// Estimate the number of chunks for a given text length.
     ///
     /// Useful for pre-allocation. May be approximate.
@@ -156,6 +290,21 @@ impl<T: Chunker + ?Sized> SlabSource for T {
         self.chunk(text)
     }
 
+    fn try_slabs(&self, text: &str) -> Result<Vec<Slab>> {
+        self.try_chunk(text)
+    }
+
+    fn slab_refs<'a>(&self, text: &'a str) -> Vec<SlabRef<'a>> {
+        self.chunk_borrowed(text)
+    }
+
+    fn slabs_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Slab> + 'a
+    where
+        Self: Sized,
+    {
+        self.chunk_iter(text)
+    }
+
     fn estimate_slabs(&self, text_len: usize) -> usize {
         self.estimate_chunks(text_len)
     }
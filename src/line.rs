@@ -0,0 +1,141 @@
+//! Line numbers for byte offsets.
+
+use std::ops::{Range, RangeInclusive};
+
+use crate::Slab;
+
+/// Metadata key holding the 1-based first line of a slab, written by
+/// [`attach_line_numbers`].
+pub const START_LINE_KEY: &str = "start_line";
+/// Metadata key holding the 1-based last line of a slab (inclusive).
+pub const END_LINE_KEY: &str = "end_line";
+
+/// Maps byte offsets in one source string to 1-based line numbers.
+///
+/// Lines end at `\n`; a preceding `\r` belongs to the line it ends. Building
+/// the index scans the source once, and each lookup is a binary search.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::LineIndex;
+///
+/// let text = "fn main() {\n    run();\n}\n";
+/// let lines = LineIndex::new(text);
+///
+/// assert_eq!(lines.line(0), 1);
+/// assert_eq!(lines.line(16), 2);
+/// assert_eq!(lines.lines(12..24), 2..=3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Byte offset where each line starts. Always begins with 0.
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    /// Index the line starts of `text`.
+    #[must_use]
+    pub fn new(text: &str) -> Self {
+        let mut starts = vec![0];
+        starts.extend(
+            text.bytes()
+                .enumerate()
+                .filter(|&(_, b)| b == b'\n')
+                .map(|(i, _)| i + 1),
+        );
+        Self { starts }
+    }
+
+    /// The 1-based line containing byte `offset`.
+    ///
+    /// An offset on a `\n` belongs to the line that newline ends. Offsets past
+    /// the end of the source map to the last line.
+    #[must_use]
+    pub fn line(&self, offset: usize) -> usize {
+        self.starts.partition_point(|&start| start <= offset)
+    }
+
+    /// The 1-based lines touched by the byte span `start..end`, inclusive.
+    ///
+    /// The end line is the line of the span's last byte, so a span ending
+    /// just after a newline does not reach the following line. An empty span
+    /// maps to the line of `start`.
+    #[must_use]
+    pub fn lines(&self, span: Range<usize>) -> RangeInclusive<usize> {
+        let first = self.line(span.start);
+        let last = if span.end > span.start {
+            self.line(span.end - 1)
+        } else {
+            first
+        };
+        first..=last
+    }
+
+    /// Number of lines in the source. An empty source has one empty line.
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+}
+
+/// Store each slab's 1-based, inclusive line range in its metadata.
+///
+/// Lines are written under [`START_LINE_KEY`] and [`END_LINE_KEY`], ready for
+/// `file.rs:123-180` style links. `slabs` must hold byte offsets into
+/// `source`.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{attach_line_numbers, slabs_from_byte_ranges, END_LINE_KEY, START_LINE_KEY};
+///
+/// let text = "a\nb\nc\n";
+/// let mut slabs = slabs_from_byte_ranges(text, &[0..2, 2..6]).unwrap();
+/// attach_line_numbers(text, &mut slabs);
+///
+/// assert_eq!(slabs[1].metadata[START_LINE_KEY], "2");
+/// assert_eq!(slabs[1].metadata[END_LINE_KEY], "3");
+/// ```
+pub fn attach_line_numbers(source: &str, slabs: &mut [Slab]) {
+    let index = LineIndex::new(source);
+    for slab in slabs {
+        let lines = index.lines(slab.span());
+        slab.metadata
+            .insert(START_LINE_KEY.to_string(), lines.start().to_string());
+        slab.metadata
+            .insert(END_LINE_KEY.to_string(), lines.end().to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newline_belongs_to_the_line_it_ends() {
+        let index = LineIndex::new("ab\r\ncd");
+
+        assert_eq!(index.line(2), 1);
+        assert_eq!(index.line(3), 1);
+        assert_eq!(index.line(4), 2);
+        assert_eq!(index.line_count(), 2);
+    }
+
+    #[test]
+    fn empty_span_and_empty_source_are_line_one() {
+        let index = LineIndex::new("");
+
+        assert_eq!(index.lines(0..0), 1..=1);
+        assert_eq!(index.line(10), 1);
+    }
+
+    #[test]
+    fn trailing_newline_starts_an_empty_last_line() {
+        let index = LineIndex::new("a\n");
+
+        assert_eq!(index.line_count(), 2);
+        assert_eq!(index.lines(0..2), 1..=1);
+        assert_eq!(index.line(2), 2);
+    }
+}
@@ -0,0 +1,69 @@
+//! Vector arithmetic shared by the pooling code.
+
+/// Mean of `vectors` over the first `dim` components, L2-normalized.
+///
+/// Missing components count as zero. An empty input, or a mean whose norm is
+/// effectively zero, yields the unnormalized mean (all zeros when empty).
+pub(crate) fn normalized_mean<'a, I>(vectors: I, dim: usize) -> Vec<f32>
+where
+    I: IntoIterator<Item = &'a [f32]>,
+{
+    let mut result = vec![0.0; dim];
+    let mut count = 0usize;
+
+    for vector in vectors {
+        debug_assert_eq!(
+            vector.len(),
+            dim,
+            "token embedding dimension mismatch: expected {}, got {}",
+            dim,
+            vector.len()
+        );
+        for (sum, &v) in result.iter_mut().zip(vector) {
+            *sum += v;
+        }
+        count += 1;
+    }
+
+    if count == 0 {
+        return result;
+    }
+    let count = count as f32;
+    for v in &mut result {
+        *v /= count;
+    }
+    l2_normalize(&mut result);
+    result
+}
+
+/// Scale `vector` to unit L2 norm, leaving near-zero vectors unchanged.
+pub(crate) fn l2_normalize(vector: &mut [f32]) {
+    let norm: f32 = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 1e-9 {
+        for v in vector {
+            *v /= norm;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mean_is_normalized() {
+        let a = [3.0, 0.0];
+        let b = [3.0, 8.0];
+
+        let mean = normalized_mean([&a[..], &b[..]], 2);
+
+        assert!((mean[0] - 0.6).abs() < 1e-6);
+        assert!((mean[1] - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn empty_and_zero_means_stay_zero() {
+        assert_eq!(normalized_mean(std::iter::empty(), 3), [0.0; 3]);
+        assert_eq!(normalized_mean([&[0.0, 0.0][..]], 2), [0.0; 2]);
+    }
+}
@@ -0,0 +1,327 @@
+//! Building and packing slabs from pre-split input.
+
+use crate::slab::validate_byte_range;
+use crate::{Result, Slab};
+
+/// Join pre-split segments into one source string and slab each segment.
+///
+/// Use this when input arrives already split (paragraph rows from a database,
+/// one string per PDF page) and no single source string exists yet. Segments
+/// are joined with `separator`; the returned string is the source that every
+/// slab's offsets refer to. Slab `i` covers segment `i` exactly, with byte and
+/// character offsets set.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::slabs_from_segments;
+///
+/// let pages = ["Page one.", "Page two."];
+/// let (source, slabs) = slabs_from_segments(&pages, "\n\n");
+///
+/// assert_eq!(source, "Page one.\n\nPage two.");
+/// assert_eq!(&source[slabs[1].span()], "Page two.");
+/// assert_eq!(slabs[1].char_span(), Some(11..20));
+/// ```
+#[must_use]
+pub fn slabs_from_segments<S: AsRef<str>>(segments: &[S], separator: &str) -> (String, Vec<Slab>) {
+    let total: usize = segments.iter().map(|s| s.as_ref().len()).sum::<usize>()
+        + separator.len() * segments.len().saturating_sub(1);
+    let mut source = String::with_capacity(total);
+    let mut slabs = Vec::with_capacity(segments.len());
+    let mut char_offset = 0;
+    let separator_chars = separator.chars().count();
+
+    for (index, segment) in segments.iter().enumerate() {
+        let segment = segment.as_ref();
+        if index > 0 {
+            source.push_str(separator);
+            char_offset += separator_chars;
+        }
+        let start = source.len();
+        source.push_str(segment);
+        let chars = segment.chars().count();
+        slabs.push(
+            Slab::new(segment, start, source.len(), index)
+                .with_char_offsets(char_offset, char_offset + chars),
+        );
+        char_offset += chars;
+    }
+
+    (source, slabs)
+}
+
+/// Greedily merge consecutive slabs while the merged span fits `max_len`.
+///
+/// `len` measures a candidate merged text, typically a token count or
+/// [`str::len`] for bytes. Slabs are merged in order, and each merged slab
+/// spans from its first member's start to its last member's end in `source`,
+/// including separators between them. A single slab already over `max_len`
+/// is kept on its own rather than split. Output indices are renumbered from
+/// zero; metadata is not carried over.
+///
+/// # Errors
+///
+/// Returns a span error if a merged range is not a valid slice of `source`.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{pack_slabs, slabs_from_segments};
+///
+/// let (source, paragraphs) = slabs_from_segments(&["aaaa", "bb", "cccccc", "d"], "\n");
+/// let packed = pack_slabs(&source, &paragraphs, 8, str::len).unwrap();
+///
+/// let texts: Vec<&str> = packed.iter().map(|s| s.text.as_str()).collect();
+/// assert_eq!(texts, ["aaaa\nbb", "cccccc\nd"]);
+/// ```
+pub fn pack_slabs<F>(source: &str, slabs: &[Slab], max_len: usize, len: F) -> Result<Vec<Slab>>
+where
+    F: Fn(&str) -> usize,
+{
+    spans_to_slabs(source, greedy_spans(source, slabs, max_len, &len))
+}
+
+/// Pack consecutive slabs into evenly sized groups under `max_len`.
+///
+/// Produces as many slabs as [`pack_slabs`] would, but spreads length evenly
+/// across them instead of filling each to `max_len`: three groups of about
+/// 600 bytes instead of 900, 900, and 90. The even limit is the smallest one
+/// that still needs no more groups than greedy packing at `max_len`, found by
+/// binary search over lengths, so `len` runs `O(n log max_len)` times.
+/// Oversized slabs and output fields behave as in [`pack_slabs`].
+///
+/// # Errors
+///
+/// Returns a span error if a merged range is not a valid slice of `source`.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{pack_slabs, pack_slabs_balanced, slabs_from_segments};
+///
+/// let (source, units) = slabs_from_segments(&["aaaaaaaaa", "bbbbbbbbb", "cc"], "");
+///
+/// let greedy = pack_slabs(&source, &units, 18, str::len).unwrap();
+/// let balanced = pack_slabs_balanced(&source, &units, 18, str::len).unwrap();
+///
+/// let lens = |slabs: &[slabs::Slab]| slabs.iter().map(|s| s.len()).collect::<Vec<_>>();
+/// assert_eq!(lens(&greedy), [18, 2]);
+/// assert_eq!(lens(&balanced), [9, 11]);
+/// ```
+pub fn pack_slabs_balanced<F>(
+    source: &str,
+    slabs: &[Slab],
+    max_len: usize,
+    len: F,
+) -> Result<Vec<Slab>>
+where
+    F: Fn(&str) -> usize,
+{
+    let mut best = greedy_spans(source, slabs, max_len, &len);
+    let groups = best.len();
+    let (mut low, mut high) = (0, max_len);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let spans = greedy_spans(source, slabs, mid, &len);
+        if spans.len() <= groups {
+            best = spans;
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    spans_to_slabs(source, best)
+}
+
+fn greedy_spans<F>(source: &str, slabs: &[Slab], max_len: usize, len: &F) -> Vec<(usize, usize)>
+where
+    F: Fn(&str) -> usize,
+{
+    let mut spans = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+
+    for slab in slabs {
+        current = match current {
+            None => Some((slab.start, slab.end)),
+            Some((start, end)) => {
+                let merged_end = end.max(slab.end);
+                match source.get(start..merged_end) {
+                    Some(merged) if len(merged) <= max_len => Some((start, merged_end)),
+                    _ => {
+                        spans.push((start, end));
+                        Some((slab.start, slab.end))
+                    }
+                }
+            }
+        };
+    }
+    spans.extend(current);
+    spans
+}
+
+fn spans_to_slabs(source: &str, spans: Vec<(usize, usize)>) -> Result<Vec<Slab>> {
+    spans
+        .into_iter()
+        .enumerate()
+        .map(|(index, (start, end))| Slab::from_byte_range(source, start..end, index))
+        .collect()
+}
+
+/// Merge slabs shorter than `min_len` into their neighbours.
+///
+/// Consecutive slabs are grouped until the group's span reaches `min_len` as
+/// measured by `len`, then the group becomes one slab. A short group left at
+/// the end (the typical tiny trailing fragment) is merged into the previous
+/// slab. Merged slabs span from the first member's start to the last
+/// member's end in `source`. Output indices are renumbered from zero;
+/// metadata is not carried over. If the whole input is shorter than
+/// `min_len`, the result is a single slab.
+///
+/// # Errors
+///
+/// Returns a span error if a merged range is not a valid slice of `source`.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{merge_short_slabs, slabs_from_byte_ranges};
+///
+/// let text = "A long first part. A long second part. End.";
+/// let slabs = slabs_from_byte_ranges(text, &[0..18, 19..38, 39..43]).unwrap();
+///
+/// let merged = merge_short_slabs(text, &slabs, 10, str::len).unwrap();
+/// let texts: Vec<&str> = merged.iter().map(|s| s.text.as_str()).collect();
+/// assert_eq!(texts, ["A long first part.", "A long second part. End."]);
+/// ```
+pub fn merge_short_slabs<F>(
+    source: &str,
+    slabs: &[Slab],
+    min_len: usize,
+    len: F,
+) -> Result<Vec<Slab>>
+where
+    F: Fn(&str) -> usize,
+{
+    let mut spans: Vec<(usize, usize)> = Vec::new();
+    let mut group: Option<(usize, usize)> = None;
+
+    for slab in slabs {
+        let (start, end) = match group {
+            Some((start, end)) => (start, end.max(slab.end)),
+            None => (slab.start, slab.end),
+        };
+        validate_byte_range(source, start..end)?;
+        if len(&source[start..end]) >= min_len {
+            spans.push((start, end));
+            group = None;
+        } else {
+            group = Some((start, end));
+        }
+    }
+    if let Some((start, end)) = group {
+        match spans.last_mut() {
+            Some(last) => last.1 = last.1.max(end),
+            None => spans.push((start, end)),
+        }
+    }
+
+    spans_to_slabs(source, spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_offsets_count_multibyte_separators() {
+        let (source, slabs) = slabs_from_segments(&["日本", "語"], " · ");
+
+        assert_eq!(source, "日本 · 語");
+        assert_eq!(slabs[1].text, "語");
+        assert_eq!(&source[slabs[1].span()], "語");
+        assert_eq!(slabs[1].char_span(), Some(5..6));
+    }
+
+    #[test]
+    fn empty_segment_list_gives_empty_source() {
+        let (source, slabs) = slabs_from_segments::<&str>(&[], "\n");
+
+        assert!(source.is_empty());
+        assert!(slabs.is_empty());
+    }
+
+    #[test]
+    fn oversized_slab_stays_alone() {
+        let (source, slabs) = slabs_from_segments(&["a", "bbbbbbbbbb", "c"], " ");
+
+        let packed = pack_slabs(&source, &slabs, 4, str::len).unwrap();
+
+        assert_eq!(
+            packed.iter().map(|s| s.text.as_str()).collect::<Vec<_>>(),
+            ["a", "bbbbbbbbbb", "c"]
+        );
+        assert_eq!(
+            packed.iter().map(|s| s.index).collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn packing_respects_custom_length() {
+        let (source, slabs) = slabs_from_segments(&["one two", "three", "four five six"], " ");
+        let words = |text: &str| text.split_whitespace().count();
+
+        let packed = pack_slabs(&source, &slabs, 3, words).unwrap();
+
+        assert_eq!(
+            packed.iter().map(|s| s.text.as_str()).collect::<Vec<_>>(),
+            ["one two three", "four five six"]
+        );
+    }
+
+    #[test]
+    fn short_leading_slabs_merge_forward() {
+        let (source, slabs) = slabs_from_segments(&["a", "b", "cccc", "dddd"], " ");
+
+        let merged = merge_short_slabs(&source, &slabs, 3, str::len).unwrap();
+
+        assert_eq!(
+            merged.iter().map(|s| s.text.as_str()).collect::<Vec<_>>(),
+            ["a b", "cccc", "dddd"]
+        );
+    }
+
+    #[test]
+    fn all_short_input_collapses_to_one_slab() {
+        let (source, slabs) = slabs_from_segments(&["a", "b"], " ");
+
+        let merged = merge_short_slabs(&source, &slabs, 100, str::len).unwrap();
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].text, "a b");
+        assert_eq!(merged[0].char_span(), Some(0..3));
+    }
+
+    #[test]
+    fn balanced_packing_keeps_greedy_group_count() {
+        let segments = ["aaaa", "bbbb", "cccc", "dddd", "e"];
+        let (source, units) = slabs_from_segments(&segments, " ");
+
+        let greedy = pack_slabs(&source, &units, 14, str::len).unwrap();
+        let balanced = pack_slabs_balanced(&source, &units, 14, str::len).unwrap();
+
+        assert_eq!(greedy.len(), balanced.len());
+        assert_eq!(
+            balanced.iter().map(|s| s.text.as_str()).collect::<Vec<_>>(),
+            ["aaaa bbbb", "cccc dddd e"]
+        );
+    }
+
+    #[test]
+    fn balanced_packing_of_nothing_is_empty() {
+        let packed = pack_slabs_balanced("", &[], 10, str::len).unwrap();
+
+        assert!(packed.is_empty());
+    }
+}
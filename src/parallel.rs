@@ -0,0 +1,76 @@
+//! Parallel slab construction across many documents.
+
+use rayon::prelude::*;
+
+use crate::{Slab, SlabSource};
+
+/// Run `source` over every document on the rayon thread pool.
+///
+/// Returns one `Vec<Slab>` per document, in input order, each with byte and
+/// character offsets relative to its own document. Work is split across the
+/// current rayon pool; install a custom pool with `ThreadPool::install` to
+/// bound thread count.
+///
+/// Requires the `parallel` feature.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{slabs_many, Chunker, Slab};
+///
+/// struct Whole;
+///
+/// impl Chunker for Whole {
+///     fn chunk_bytes(&self, text: &str) -> Vec<Slab> {
+///         vec![Slab::new(text, 0, text.len(), 0)]
+///     }
+/// }
+///
+/// let docs = ["naïve", "café au lait"];
+/// let out = slabs_many(&Whole, &docs);
+///
+/// assert_eq!(out.len(), 2);
+/// assert_eq!(out[0][0].char_span(), Some(0..5));
+/// assert_eq!(out[1][0].text, "café au lait");
+/// ```
+#[must_use]
+pub fn slabs_many<S, D>(source: &S, documents: &[D]) -> Vec<Vec<Slab>>
+where
+    S: SlabSource + ?Sized,
+    D: AsRef<str> + Sync,
+{
+    documents
+        .par_iter()
+        .map(|doc| source.slabs(doc.as_ref()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Chunker;
+
+    struct Halves;
+
+    impl Chunker for Halves {
+        fn chunk_bytes(&self, text: &str) -> Vec<Slab> {
+            let mid = text.len() / 2;
+            vec![
+                Slab::new(&text[..mid], 0, mid, 0),
+                Slab::new(&text[mid..], mid, text.len(), 1),
+            ]
+        }
+    }
+
+    #[test]
+    fn output_order_matches_input_order() {
+        let docs: Vec<String> = (0..200).map(|i| format!("{i:04}{i:04}")).collect();
+
+        let out = slabs_many(&Halves, &docs);
+
+        for (i, slabs) in out.iter().enumerate() {
+            assert_eq!(slabs[0].text, format!("{i:04}"));
+            assert_eq!(slabs[1].char_span(), Some(4..8));
+        }
+    }
+}
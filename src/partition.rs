@@ -0,0 +1,221 @@
+//! Bounding the input size seen by a slab source.
+
+use std::ops::Range;
+
+use crate::{compute_char_offsets, Result, Slab, SlabSource};
+
+/// A [`SlabSource`] that runs `inner` on paragraph-aligned sections of large
+/// documents.
+///
+/// Documents of at most `max_bytes` bytes go to `inner` unchanged. Larger
+/// documents are cut at paragraph boundaries (after a run of two or more
+/// `\n` or `\r\n` line breaks) into sections of at most `max_bytes`,
+/// greedily filled. `inner` runs on each section in turn, and its slabs are
+/// shifted back into document offsets and renumbered, so callers still get
+/// one slab sequence for the whole document. A single paragraph over
+/// `max_bytes` becomes its own, oversized section.
+///
+/// Use this in front of expensive boundary sources whose memory grows with
+/// input length. Slabs cannot cross a section boundary.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{Chunker, Partitioned, Slab, SlabSource};
+///
+/// struct Whole;
+///
+/// impl Chunker for Whole {
+///     fn chunk_bytes(&self, text: &str) -> Vec<Slab> {
+///         vec![Slab::new(text, 0, text.len(), 0)]
+///     }
+/// }
+///
+/// let text = "First paragraph.\n\nSecond paragraph.";
+/// let slabs = Partitioned::new(Whole, 20).slabs(text);
+///
+/// assert_eq!(slabs.len(), 2);
+/// assert_eq!(slabs[1].text, "Second paragraph.");
+/// assert_eq!(&text[slabs[1].span()], slabs[1].text);
+/// assert_eq!(slabs[1].index, 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Partitioned<S> {
+    inner: S,
+    max_bytes: usize,
+}
+
+impl<S> Partitioned<S> {
+    /// Wrap `inner` so it never sees more than one section of `max_bytes`,
+    /// except for oversized paragraphs.
+    pub fn new(inner: S, max_bytes: usize) -> Self {
+        Self { inner, max_bytes }
+    }
+
+    /// The wrapped source.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+}
+
+impl<S: SlabSource> Partitioned<S> {
+    /// Run `slab_section` on each section and shift its slabs into document
+    /// byte offsets. Character offsets are left as `slab_section` set them.
+    fn partition<F>(&self, text: &str, mut slab_section: F) -> Result<Vec<Slab>>
+    where
+        F: FnMut(&str) -> Result<Vec<Slab>>,
+    {
+        if text.len() <= self.max_bytes {
+            return slab_section(text);
+        }
+
+        let mut slabs = Vec::with_capacity(self.estimate_slabs(text.len()));
+        for section in sections(text, self.max_bytes) {
+            let offset = section.start;
+            for mut slab in slab_section(&text[section])? {
+                slab.start += offset;
+                slab.end += offset;
+                slab.core_start = slab.core_start.map(|start| start + offset);
+                slab.core_end = slab.core_end.map(|end| end + offset);
+                slab.index = slabs.len();
+                slabs.push(slab);
+            }
+        }
+        Ok(slabs)
+    }
+}
+
+impl<S: SlabSource> SlabSource for Partitioned<S> {
+    fn slab_bytes(&self, text: &str) -> Vec<Slab> {
+        // The section closure never fails, so nothing is dropped here.
+        self.partition(text, |section| Ok(self.inner.slab_bytes(section)))
+            .unwrap_or_default()
+    }
+
+    /// Forward to the inner source's [`try_slabs`](SlabSource::try_slabs) for
+    /// every section, stopping at the first error.
+    fn try_slabs(&self, text: &str) -> Result<Vec<Slab>> {
+        let mut slabs = self.partition(text, |section| self.inner.try_slabs(section))?;
+        compute_char_offsets(text, &mut slabs);
+        Ok(slabs)
+    }
+
+    fn estimate_slabs(&self, text_len: usize) -> usize {
+        self.inner.estimate_slabs(text_len)
+    }
+}
+
+/// Greedily group paragraphs into sections of at most `max_bytes`.
+fn sections(text: &str, max_bytes: usize) -> Vec<Range<usize>> {
+    let bytes = text.as_bytes();
+    let mut ends = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let (mut j, mut breaks) = (i, 0);
+        while let Some(len) = line_break(&bytes[j..]) {
+            j += len;
+            breaks += 1;
+        }
+        if breaks >= 2 {
+            ends.push(j);
+        }
+        i = j.max(i + 1);
+    }
+    if ends.last() != Some(&text.len()) {
+        ends.push(text.len());
+    }
+
+    let mut sections = Vec::new();
+    let (mut start, mut end) = (0, 0);
+    for paragraph_end in ends {
+        if paragraph_end - start > max_bytes && end > start {
+            sections.push(start..end);
+            start = end;
+        }
+        end = paragraph_end;
+    }
+    if end > start {
+        sections.push(start..end);
+    }
+    sections
+}
+
+/// Length of the `\n` or `\r\n` line break at the start of `bytes`.
+fn line_break(bytes: &[u8]) -> Option<usize> {
+    match bytes {
+        [b'\n', ..] => Some(1),
+        [b'\r', b'\n', ..] => Some(2),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Lines;
+
+    #[test]
+    fn sections_fill_greedily_and_cover_the_text() {
+        let text = "aa\n\nbb\n\n\ncc\n\ndddddddd";
+
+        let sections = sections(text, 9);
+
+        assert_eq!(sections, [0..9, 9..13, 13..21]);
+        assert_eq!(&text[sections[0].clone()], "aa\n\nbb\n\n\n");
+    }
+
+    #[test]
+    fn crlf_blank_lines_are_paragraph_breaks() {
+        let text = "aa\r\n\r\nbb\r\ncc\n\r\ndd";
+
+        let sections = sections(text, 6);
+
+        assert_eq!(sections, [0..6, 6..15, 15..17]);
+        assert_eq!(&text[sections[1].clone()], "bb\r\ncc\n\r\n");
+    }
+
+    #[test]
+    fn small_documents_pass_through() {
+        let text = "one\ntwo";
+
+        assert_eq!(Partitioned::new(Lines, 64).slabs(text), Lines.slabs(text));
+    }
+
+    #[test]
+    fn partitioned_offsets_match_unpartitioned_offsets() {
+        let text = "é one\n\nü two\n\nö three\n";
+
+        let whole = Lines.slabs(text);
+        let parted = Partitioned::new(Lines, 10).slabs(text);
+
+        assert_eq!(parted, whole);
+    }
+
+    #[test]
+    fn inner_errors_surface_through_try_slabs() {
+        struct Picky;
+
+        impl SlabSource for Picky {
+            fn slab_bytes(&self, text: &str) -> Vec<Slab> {
+                vec![Slab::new(text, 0, text.len(), 0)]
+            }
+
+            fn try_slabs(&self, text: &str) -> Result<Vec<Slab>> {
+                if text.contains("bad") {
+                    return Err(crate::Error::Transform("unparseable section".into()));
+                }
+                Ok(self.slabs(text))
+            }
+        }
+
+        let partitioned = Partitioned::new(Picky, 8);
+
+        let ok = partitioned.try_slabs("good\n\nfine\n\nok").unwrap();
+        assert_eq!(ok[1].span(), 6..14);
+        assert_eq!(ok[1].char_span(), Some(6..14));
+        assert!(matches!(
+            partitioned.try_slabs("good\n\nbad\n\nok"),
+            Err(crate::Error::Transform(_))
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+//! Reusable post-processing steps around a slab source.
+
+use crate::slab::validate_slabs;
+use crate::{compute_char_offsets, Result, Slab, SlabSource, TrimMode};
+
+/// A post-processing step over the slabs of one document.
+///
+/// `source` is the document the slabs point into. Transforms may merge,
+/// split, drop, trim, or annotate slabs, but returned offsets must still be
+/// byte offsets into `source`.
+///
+/// Closures `Fn(&str, Vec<Slab>) -> Result<Vec<Slab>>` implement this trait,
+/// so crate helpers such as [`merge_short_slabs`](crate::merge_short_slabs)
+/// can be used inline. [`TrimMode`] trims every slab. Report failures of
+/// the step itself with [`Error::Transform`](crate::Error::Transform).
+pub trait SlabTransform: Send + Sync {
+    /// Transform the slabs of `source`.
+    fn transform(&self, source: &str, slabs: Vec<Slab>) -> Result<Vec<Slab>>;
+}
+
+impl<F> SlabTransform for F
+where
+    F: Fn(&str, Vec<Slab>) -> Result<Vec<Slab>> + Send + Sync,
+{
+    fn transform(&self, source: &str, slabs: Vec<Slab>) -> Result<Vec<Slab>> {
+        self(source, slabs)
+    }
+}
+
+impl SlabTransform for TrimMode {
+    fn transform(&self, _source: &str, slabs: Vec<Slab>) -> Result<Vec<Slab>> {
+        Ok(slabs.into_iter().map(|slab| slab.trimmed(*self)).collect())
+    }
+}
+
+/// A slab source followed by an ordered list of [`SlabTransform`]s.
+///
+/// Only post-processing is modelled: a step that rewrites the input text
+/// before boundary selection would invalidate offsets into the original
+/// document.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{merge_short_slabs, slabs_from_byte_ranges, Chunker, Pipeline, Slab, TrimMode};
+///
+/// struct Fixed;
+///
+/// impl Chunker for Fixed {
+///     fn chunk_bytes(&self, text: &str) -> Vec<Slab> {
+///         slabs_from_byte_ranges(text, &[0..2, 2..4, 4..16]).unwrap_or_default()
+///     }
+/// }
+///
+/// let pipeline = Pipeline::new(Fixed)
+///     .then(|source: &str, slabs: Vec<Slab>| merge_short_slabs(source, &slabs, 8, str::len))
+///     .then(TrimMode::Both);
+///
+/// let slabs = pipeline.run("a\nb\nlonger line\n").unwrap();
+/// let texts: Vec<&str> = slabs.iter().map(|s| s.text.as_str()).collect();
+/// assert_eq!(texts, ["a\nb\nlonger line"]);
+/// ```
+pub struct Pipeline<S> {
+    source: S,
+    transforms: Vec<Box<dyn SlabTransform>>,
+}
+
+impl<S: SlabSource> Pipeline<S> {
+    /// Start a pipeline from `source` with no transforms.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            transforms: Vec::new(),
+        }
+    }
+
+    /// Append a transform, run after every earlier one.
+    #[must_use]
+    pub fn then(mut self, transform: impl SlabTransform + 'static) -> Self {
+        self.transforms.push(Box::new(transform));
+        self
+    }
+
+    /// Run the source and every transform on `text`.
+    ///
+    /// Slabs come from [`SlabSource::try_slabs`]. Character offsets are
+    /// recomputed after the last transform, so transforms only need to keep
+    /// byte offsets right.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from the source or a transform, and
+    /// [`Error::InvalidByteSpan`](crate::Error::InvalidByteSpan) or
+    /// [`Error::NonCharBoundary`](crate::Error::NonCharBoundary) if a
+    /// transform leaves a span that is not a valid slice of `text`.
+    pub fn run(&self, text: &str) -> Result<Vec<Slab>> {
+        let mut slabs = self.source.try_slabs(text)?;
+        for transform in &self.transforms {
+            slabs = transform.transform(text, slabs)?;
+        }
+        validate_slabs(text, &slabs)?;
+        compute_char_offsets(text, &mut slabs);
+        Ok(slabs)
+    }
+}
+
+impl<S: std::fmt::Debug> std::fmt::Debug for Pipeline<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("source", &self.source)
+            .field("transforms", &self.transforms.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Lines;
+    use crate::Error;
+
+    #[test]
+    fn transforms_run_in_order_and_char_offsets_are_refreshed() {
+        let pipeline =
+            Pipeline::new(Lines)
+                .then(TrimMode::End)
+                .then(|_: &str, mut slabs: Vec<Slab>| {
+                    slabs.retain(|slab| slab.len() > 1);
+                    Ok(slabs)
+                });
+
+        let slabs = pipeline.run("é\nab\nc\ndé").unwrap();
+
+        assert_eq!(
+            slabs.iter().map(|s| s.text.as_str()).collect::<Vec<_>>(),
+            ["é", "ab", "dé"]
+        );
+        assert_eq!(slabs[2].char_span(), Some(7..9));
+    }
+
+    #[test]
+    fn transform_errors_stop_the_pipeline() {
+        let pipeline = Pipeline::new(Lines)
+            .then(|_: &str, _: Vec<Slab>| Err(Error::Transform("rejected".into())))
+            .then(|_: &str, _: Vec<Slab>| -> Result<Vec<Slab>> { unreachable!() });
+
+        assert!(matches!(pipeline.run("a\nb"), Err(Error::Transform(_))));
+    }
+
+    #[test]
+    fn transform_spans_outside_the_text_are_errors() {
+        let pipeline =
+            Pipeline::new(Lines).then(|_: &str, _: Vec<Slab>| Ok(vec![Slab::new("", 0, 99, 0)]));
+
+        let err = pipeline.run("a\nb").unwrap_err();
+
+        assert!(matches!(err, Error::InvalidByteSpan { end: 99, .. }));
+    }
+}
@@ -0,0 +1,234 @@
+//! Offset-preserving redaction of stored slab text.
+
+use std::ops::Range;
+
+use crate::{Error, Result, Slab, WINDOW_END_KEY, WINDOW_KEY, WINDOW_START_KEY};
+
+/// The byte written over redacted text.
+pub const REDACTION_BYTE: u8 = b'*';
+
+/// One slab field affected by a redaction.
+///
+/// Keep these as an audit trail: they say which stored slabs changed, where,
+/// and why, so only those slabs need to be re-embedded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RedactionRecord {
+    /// Position of the slab in the slice passed to [`redact_slabs`].
+    pub position: usize,
+    /// The slab's [`Slab::index`].
+    pub index: usize,
+    /// Metadata key whose value was redacted, or `None` for the slab text.
+    pub key: Option<String>,
+    /// Redacted byte span in source coordinates, clipped to the field.
+    pub span: Range<usize>,
+    /// Caller-supplied reason, for example a deletion request id.
+    pub reason: String,
+}
+
+/// Redact a source byte span in every slab that overlaps it.
+///
+/// Each redacted byte is replaced with [`REDACTION_BYTE`], so slab byte
+/// lengths and byte offsets are unchanged and spans still line up with the
+/// rest of the index. Multi-byte characters become several placeholder
+/// bytes; character offsets keep describing the original source, but the
+/// redacted text's own character count can grow.
+///
+/// `span` is in source byte coordinates, like [`Slab::start`]. Slabs that do
+/// not overlap it are left alone, and an empty span changes nothing. Returns
+/// one record per changed field.
+///
+/// Sentence windows written by
+/// [`attach_sentence_windows`](crate::attach_sentence_windows) copy
+/// neighbouring source text into metadata, so the [`WINDOW_KEY`] value is
+/// redacted too when its [`WINDOW_START_KEY`]..[`WINDOW_END_KEY`] span
+/// overlaps `span`, even if the slab text does not. **No other metadata is
+/// scrubbed.** Any other metadata value that holds copied source text must be
+/// redacted by the caller.
+///
+/// # Errors
+///
+/// Returns [`Error::NonCharBoundary`] if the span cuts through a character in
+/// an overlapping slab's text or window, or [`Error::InvalidByteSpan`] if a
+/// slab's text or window is shorter than its byte span. Nothing is modified
+/// when an error is returned.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{redact_slabs, slabs_from_byte_ranges};
+///
+/// let text = "Call Ada at 555-0100. Ada replied.";
+/// let mut slabs = slabs_from_byte_ranges(text, &[0..21, 22..34]).unwrap();
+///
+/// let audit = redact_slabs(&mut slabs, 12..20, "gdpr-1234").unwrap();
+///
+/// assert_eq!(slabs[0].text, "Call Ada at ********.");
+/// assert_eq!(slabs[0].len(), 21);
+/// assert_eq!(audit.len(), 1);
+/// assert_eq!(audit[0].span, 12..20);
+/// ```
+pub fn redact_slabs(
+    slabs: &mut [Slab],
+    span: Range<usize>,
+    reason: &str,
+) -> Result<Vec<RedactionRecord>> {
+    // Validate everything before touching any slab.
+    let mut hits = Vec::new();
+    for (position, slab) in slabs.iter().enumerate() {
+        if let Some(local) = locate(&slab.text, slab.start, slab.end, &span)? {
+            hits.push((position, None, slab.start, local));
+        }
+        if let Some((start, end, window)) = window_of(slab) {
+            if let Some(local) = locate(window, start, end, &span)? {
+                hits.push((position, Some(WINDOW_KEY), start, local));
+            }
+        }
+    }
+
+    let mut records = Vec::with_capacity(hits.len());
+    for (position, key, base, local) in hits {
+        let slab = &mut slabs[position];
+        let field = match key {
+            None => &mut slab.text,
+            Some(key) => slab
+                .metadata
+                .get_mut(key)
+                .expect("window located during validation"),
+        };
+        let placeholder = (REDACTION_BYTE as char)
+            .to_string()
+            .repeat(local.end - local.start);
+        field.replace_range(local.clone(), &placeholder);
+        records.push(RedactionRecord {
+            position,
+            index: slab.index,
+            key: key.map(str::to_string),
+            span: base + local.start..base + local.end,
+            reason: reason.to_string(),
+        });
+    }
+
+    Ok(records)
+}
+
+/// The sentence window stored on `slab`, with its source byte span.
+fn window_of(slab: &Slab) -> Option<(usize, usize, &str)> {
+    let offset = |key: &str| slab.metadata.get(key)?.parse::<usize>().ok();
+    let window = slab.metadata.get(WINDOW_KEY)?;
+    Some((offset(WINDOW_START_KEY)?, offset(WINDOW_END_KEY)?, window))
+}
+
+/// Clip `span` to the field `text` covering source bytes `start..end`, and
+/// return the overlap in field-local offsets.
+fn locate(
+    text: &str,
+    start: usize,
+    end: usize,
+    span: &Range<usize>,
+) -> Result<Option<Range<usize>>> {
+    let clipped_start = span.start.max(start);
+    let clipped_end = span.end.min(end);
+    if clipped_start >= clipped_end {
+        return Ok(None);
+    }
+    let local = clipped_start - start..clipped_end - start;
+    if local.end > text.len() {
+        return Err(Error::InvalidByteSpan {
+            start,
+            end,
+            len: start + text.len(),
+        });
+    }
+    for offset in [local.start, local.end] {
+        if !text.is_char_boundary(offset) {
+            return Err(Error::NonCharBoundary {
+                offset: start + offset,
+            });
+        }
+    }
+    Ok(Some(local))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slabs_from_byte_ranges;
+
+    #[test]
+    fn overlapping_slabs_are_all_redacted() {
+        let text = "secret word here";
+        let mut slabs = slabs_from_byte_ranges(text, &[0..11, 7..16]).unwrap();
+
+        let audit = redact_slabs(&mut slabs, 0..11, "r").unwrap();
+
+        assert_eq!(slabs[0].text, "***********");
+        assert_eq!(slabs[1].text, "**** here");
+        assert_eq!(
+            audit.iter().map(|r| r.span.clone()).collect::<Vec<_>>(),
+            [0..11, 7..11]
+        );
+    }
+
+    #[test]
+    fn multibyte_characters_keep_byte_length() {
+        let text = "café ok";
+        let mut slabs = vec![Slab::from_byte_range(text, 0..8, 0).unwrap()];
+
+        redact_slabs(&mut slabs, 0..5, "r").unwrap();
+
+        assert_eq!(slabs[0].text, "***** ok");
+        assert_eq!(slabs[0].len(), text.len());
+        assert_eq!(slabs[0].char_span(), Some(0..7));
+    }
+
+    #[test]
+    fn cutting_a_character_fails_without_changes() {
+        let text = "ab café";
+        let mut slabs = slabs_from_byte_ranges(text, &[0..2, 3..8]).unwrap();
+
+        let err = redact_slabs(&mut slabs, 0..7, "r").unwrap_err();
+
+        assert!(matches!(err, Error::NonCharBoundary { offset: 7 }));
+        assert_eq!(slabs[0].text, "ab");
+    }
+
+    #[test]
+    fn disjoint_span_changes_nothing() {
+        let mut slabs = vec![Slab::from_byte_range("abc def", 0..3, 0).unwrap()];
+
+        let audit = redact_slabs(&mut slabs, 4..7, "r").unwrap();
+
+        assert!(audit.is_empty());
+        assert_eq!(slabs[0].text, "abc");
+    }
+
+    #[test]
+    fn sentence_windows_are_redacted_with_audit_records() {
+        let text = "One. Secret Ada. Two.";
+        let mut slabs = slabs_from_byte_ranges(text, &[0..4, 5..16, 17..21]).unwrap();
+        crate::attach_sentence_windows(text, &mut slabs, 1).unwrap();
+
+        let audit = redact_slabs(&mut slabs, 12..15, "gdpr").unwrap();
+
+        assert_eq!(slabs[0].metadata[WINDOW_KEY], "One. Secret ***.");
+        assert_eq!(slabs[1].text, "Secret ***.");
+        assert_eq!(slabs[2].metadata[WINDOW_KEY], "Secret ***. Two.");
+        assert!(slabs
+            .iter()
+            .all(|slab| !slab.metadata[WINDOW_KEY].contains("Ada")));
+        assert_eq!(
+            audit
+                .iter()
+                .map(|r| (r.position, r.key.as_deref()))
+                .collect::<Vec<_>>(),
+            [
+                (0, Some(WINDOW_KEY)),
+                (1, None),
+                (1, Some(WINDOW_KEY)),
+                (2, Some(WINDOW_KEY))
+            ]
+        );
+        assert!(audit.iter().all(|r| r.span == (12..15)));
+    }
+}
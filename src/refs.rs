@@ -0,0 +1,177 @@
+//! Intra-document reference resolution.
+
+use std::collections::HashMap;
+
+use crate::{Slab, SlabEdge, SlabEdgeKind};
+
+/// Metadata key listing the [`Slab::index`] values a slab links to, written by
+/// [`attach_references`].
+pub const REFERENCES_KEY: &str = "references";
+
+/// Resolve intra-document links to the slabs holding their targets.
+///
+/// This crate does not parse document formats. The caller extracts
+/// `anchors`, each a name and the source byte offset where it is defined,
+/// and `links`, each the source byte offset of a link and the anchor name it
+/// points to, with whatever parser fits the format: Markdown headings and
+/// `[text](#anchor)` links, HTML ids, LaTeX `\label`/`\ref`, and so on. When
+/// a name is defined more than once, the first definition wins.
+///
+/// Each resolved link yields a [`SlabEdgeKind::Reference`] edge from the
+/// smallest slab containing the link to the smallest slab containing the
+/// anchor. Links within one slab, links to unknown names, and offsets outside
+/// every slab produce no edge. Duplicate edges are collapsed.
+///
+/// Offsets are in the same coordinates as the slabs' byte spans.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{reference_edges, slabs_from_byte_ranges, SlabEdgeKind};
+///
+/// let text = "# Intro\nSee [setup](#install).\n\n# Install\nRun it.\n";
+/// let slabs = slabs_from_byte_ranges(text, &[0..31, 32..50]).unwrap();
+///
+/// // Found by the caller's Markdown parser.
+/// let anchors = [("intro", 0), ("install", 32)];
+/// let links = [(12, "install")];
+///
+/// let edges = reference_edges(&slabs, &anchors, &links);
+/// assert_eq!(edges.len(), 1);
+/// assert_eq!((edges[0].from, edges[0].to), (0, 1));
+/// assert_eq!(edges[0].kind, SlabEdgeKind::Reference);
+/// ```
+#[must_use]
+pub fn reference_edges<A, L>(
+    slabs: &[Slab],
+    anchors: &[(A, usize)],
+    links: &[(usize, L)],
+) -> Vec<SlabEdge>
+where
+    A: AsRef<str>,
+    L: AsRef<str>,
+{
+    let mut offsets: HashMap<&str, usize> = HashMap::new();
+    for (name, offset) in anchors {
+        offsets.entry(name.as_ref()).or_insert(*offset);
+    }
+
+    let mut edges: Vec<SlabEdge> = Vec::new();
+    for (link_offset, target) in links {
+        let Some(&anchor_offset) = offsets.get(target.as_ref()) else {
+            continue;
+        };
+        let (Some(from), Some(to)) = (
+            smallest_containing(slabs, *link_offset),
+            smallest_containing(slabs, anchor_offset),
+        ) else {
+            continue;
+        };
+        let edge = SlabEdge {
+            from,
+            to,
+            kind: SlabEdgeKind::Reference,
+        };
+        if from != to && !edges.contains(&edge) {
+            edges.push(edge);
+        }
+    }
+    edges
+}
+
+/// Resolve links and record them in each slab's metadata.
+///
+/// Runs [`reference_edges`] and stores the target [`Slab::index`] values,
+/// space-separated in ascending order, under [`REFERENCES_KEY`] on every slab
+/// that links elsewhere. Returns the edges.
+pub fn attach_references<A, L>(
+    slabs: &mut [Slab],
+    anchors: &[(A, usize)],
+    links: &[(usize, L)],
+) -> Vec<SlabEdge>
+where
+    A: AsRef<str>,
+    L: AsRef<str>,
+{
+    let edges = reference_edges(slabs, anchors, links);
+
+    let mut targets: Vec<Vec<usize>> = vec![Vec::new(); slabs.len()];
+    for edge in &edges {
+        targets[edge.from].push(slabs[edge.to].index);
+    }
+    for (slab, mut indices) in slabs.iter_mut().zip(targets) {
+        if indices.is_empty() {
+            continue;
+        }
+        indices.sort_unstable();
+        indices.dedup();
+        let value = indices
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        slab.metadata.insert(REFERENCES_KEY.to_string(), value);
+    }
+
+    edges
+}
+
+fn smallest_containing(slabs: &[Slab], offset: usize) -> Option<usize> {
+    slabs
+        .iter()
+        .enumerate()
+        .filter(|(_, slab)| slab.start <= offset && offset < slab.end)
+        .min_by_key(|(pos, slab)| (slab.end - slab.start, *pos))
+        .map(|(pos, _)| pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slabs_from_byte_ranges;
+
+    #[test]
+    fn links_resolve_to_the_smallest_containing_slab() {
+        let slabs = slabs_from_byte_ranges("0123456789", &[0..10, 0..4, 6..9]).unwrap();
+
+        let edges = reference_edges(&slabs, &[("a", 7)], &[(1, "a")]);
+
+        assert_eq!((edges[0].from, edges[0].to), (1, 2));
+    }
+
+    #[test]
+    fn first_anchor_definition_wins() {
+        let slabs = slabs_from_byte_ranges("aa bb cc", &[0..2, 3..5, 6..8]).unwrap();
+
+        let edges = reference_edges(&slabs, &[("x", 3), ("x", 6)], &[(0, "x")]);
+
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].to, 1);
+    }
+
+    #[test]
+    fn references_are_written_to_metadata_by_slab_index() {
+        let text = "[a](#two) [b](#three)\n# Two\n# Three\n";
+        let mut slabs = slabs_from_byte_ranges(text, &[0..22, 22..28, 28..36]).unwrap();
+        for slab in &mut slabs {
+            slab.index += 10;
+        }
+        let anchors = [("two".to_string(), 22), ("three".to_string(), 28)];
+
+        let edges = attach_references(&mut slabs, &anchors, &[(0, "two"), (10, "three")]);
+
+        assert_eq!(edges.len(), 2);
+        assert_eq!(slabs[0].metadata[REFERENCES_KEY], "11 12");
+        assert!(!slabs[1].metadata.contains_key(REFERENCES_KEY));
+    }
+
+    #[test]
+    fn unresolved_self_and_stray_links_produce_no_edges() {
+        let text = "# A\n[self](#a) [gone](#missing)\n";
+        let slabs = vec![Slab::from_byte_range(text, 0..text.len(), 0).unwrap()];
+
+        let links = [(4, "a"), (15, "missing"), (99, "a")];
+
+        assert!(reference_edges(&slabs, &[("a", 0)], &links).is_empty());
+    }
+}
@@ -0,0 +1,113 @@
+//! Destinations for slabs leaving this crate.
+
+use std::sync::mpsc::{Sender, SyncSender};
+
+use crate::{Error, Result, Slab};
+
+/// A destination for [`Slab`]s.
+///
+/// `SlabSink` is the downstream counterpart of [`SlabSource`](crate::SlabSource).
+/// Implement it around a queue producer, a channel, or a storage client when
+/// span selection and embedding run as separate stages or services.
+///
+/// Implementations for `Vec<Slab>` and `std::sync::mpsc` senders are provided.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::mpsc;
+///
+/// use slabs::{slabs_from_byte_ranges, SlabSink};
+///
+/// let text = "alpha beta";
+/// let (mut tx, rx) = mpsc::channel();
+/// tx.accept_all(slabs_from_byte_ranges(text, &[0..5, 6..10]).unwrap())
+///     .unwrap();
+/// drop(tx);
+///
+/// let received: Vec<_> = rx.iter().map(|slab| slab.text).collect();
+/// assert_eq!(received, ["alpha", "beta"]);
+/// ```
+pub trait SlabSink {
+    /// Accept one slab.
+    fn accept(&mut self, slab: Slab) -> Result<()>;
+
+    /// Accept every slab from `slabs`, stopping at the first error.
+    fn accept_all<I>(&mut self, slabs: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Slab>,
+        Self: Sized,
+    {
+        for slab in slabs {
+            self.accept(slab)?;
+        }
+        self.flush()
+    }
+
+    /// Flush any buffered slabs.
+    ///
+    /// The default implementation does nothing.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl SlabSink for Vec<Slab> {
+    fn accept(&mut self, slab: Slab) -> Result<()> {
+        self.push(slab);
+        Ok(())
+    }
+}
+
+impl SlabSink for Sender<Slab> {
+    fn accept(&mut self, slab: Slab) -> Result<()> {
+        self.send(slab)
+            .map_err(|err| Error::Sink(format!("channel closed before slab {}", err.0.index)))
+    }
+}
+
+impl SlabSink for SyncSender<Slab> {
+    fn accept(&mut self, slab: Slab) -> Result<()> {
+        self.send(slab)
+            .map_err(|err| Error::Sink(format!("channel closed before slab {}", err.0.index)))
+    }
+}
+
+impl<T: SlabSink + ?Sized> SlabSink for &mut T {
+    fn accept(&mut self, slab: Slab) -> Result<()> {
+        (**self).accept(slab)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        (**self).flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn vec_sink_preserves_order() {
+        let mut sink = Vec::new();
+        sink.accept_all(vec![Slab::new("a", 0, 1, 0), Slab::new("b", 1, 2, 1)])
+            .unwrap();
+
+        assert_eq!(
+            sink.iter().map(|slab| slab.index).collect::<Vec<_>>(),
+            [0, 1]
+        );
+    }
+
+    #[test]
+    fn closed_channel_reports_sink_error() {
+        let (mut tx, rx) = mpsc::channel();
+        drop(rx);
+
+        let err = tx.accept(Slab::new("a", 0, 1, 3)).unwrap_err();
+
+        assert!(matches!(err, Error::Sink(ref msg) if msg.contains("slab 3")));
+    }
+}
@@ -1,5 +1,6 @@
 //! The Slab type: a text span with position metadata.
 
+use std::collections::BTreeMap;
 use std::ops::Range;
 
 use crate::{Error, Result};
@@ -43,6 +44,28 @@ use crate::{Error, Result};
 ///                ^
 ///            overlap region [8..11]
 /// ```
+///
+/// The full span is the context span: the text that gets embedded. The core
+/// span is the part of it no other slab in the sequence claims, so every
+/// source byte belongs to at most one core. Use core spans to attribute an
+/// answer offset to exactly one slab. See [`compute_core_spans`].
+///
+/// ## Metadata
+///
+/// `metadata` holds string key/value pairs such as a heading path, page
+/// number, language, or speaker. Boundary sources attach what they know;
+/// vector stores can filter on it. Keys are kept sorted so serialized output
+/// is deterministic.
+///
+/// ```rust
+/// use slabs::Slab;
+///
+/// let slab = Slab::new("Install from source.", 0, 20, 0)
+///     .with_metadata("heading", "Install")
+///     .with_metadata("page", "3");
+///
+/// assert_eq!(slab.metadata.get("page").map(String::as_str), Some("3"));
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Slab {
@@ -60,6 +83,18 @@ pub struct Slab {
     pub char_end: Option<usize>,
     /// Zero-based index of this span in the sequence.
     pub index: usize,
+    /// Byte offset where this slab's unique (non-overlapping) region starts.
+    /// `None` until [`with_core_span`](Slab::with_core_span) or
+    /// [`compute_core_spans`] is called.
+    pub core_start: Option<usize>,
+    /// Byte offset where this slab's unique region ends (exclusive).
+    pub core_end: Option<usize>,
+    /// String key/value metadata attached by the boundary source.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub metadata: BTreeMap<String, String>,
 }
 
 impl Slab {
@@ -77,6 +112,9 @@ impl Slab {
             char_start: None,
             char_end: None,
             index,
+            core_start: None,
+            core_end: None,
+            metadata: BTreeMap::new(),
         }
     }
 
@@ -96,6 +134,9 @@ impl Slab {
             char_start: Some(char_start),
             char_end: Some(char_end),
             index,
+            core_start: None,
+            core_end: None,
+            metadata: BTreeMap::new(),
         })
     }
 
@@ -122,6 +163,9 @@ impl Slab {
             char_start: Some(range.start),
             char_end: Some(range.end),
             index,
+            core_start: None,
+            core_end: None,
+            metadata: BTreeMap::new(),
         })
     }
 
@@ -133,6 +177,13 @@ impl Slab {
         self
     }
 
+    /// Attach a metadata entry, replacing any previous value for `key`.
+    #[must_use]
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
     /// The length of this span in bytes.
     #[must_use]
     pub fn len(&self) -> usize {
@@ -165,6 +216,141 @@ impl Slab {
             _ => None,
         }
     }
+
+    /// Set the core (non-overlapping) byte span of this slab.
+    ///
+    /// The core span should lie within [`span`](Slab::span).
+    #[must_use]
+    pub fn with_core_span(mut self, core: Range<usize>) -> Self {
+        debug_assert!(
+            self.start <= core.start && core.start <= core.end && core.end <= self.end,
+            "core span {core:?} must lie within slab span {:?}",
+            self.span()
+        );
+        self.core_start = Some(core.start);
+        self.core_end = Some(core.end);
+        self
+    }
+
+    /// The full byte span used for embedding, including overlap with
+    /// neighbours. Same as [`span`](Slab::span).
+    #[must_use]
+    pub fn context_span(&self) -> Range<usize> {
+        self.span()
+    }
+
+    /// The unique byte span of this slab, if computed.
+    #[must_use]
+    pub fn core_span(&self) -> Option<Range<usize>> {
+        match (self.core_start, self.core_end) {
+            (Some(s), Some(e)) => Some(s..e),
+            _ => None,
+        }
+    }
+
+    /// Trim whitespace from this slab's text and shrink its offsets to match.
+    ///
+    /// Byte offsets always move with the trimmed text. Character offsets and
+    /// the core span are adjusted when set; the core span is clamped to the
+    /// trimmed span. Metadata and index are kept. A slab of only whitespace
+    /// becomes empty at its original start.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use slabs::{Slab, TrimMode};
+    ///
+    /// let text = "  Hello.  ";
+    /// let slab = Slab::from_byte_range(text, 0..10, 0).unwrap();
+    ///
+    /// let trimmed = slab.trimmed(TrimMode::Both);
+    /// assert_eq!(trimmed.text, "Hello.");
+    /// assert_eq!(trimmed.span(), 2..8);
+    /// assert_eq!(&text[trimmed.span()], trimmed.text);
+    /// ```
+    #[must_use]
+    pub fn trimmed(mut self, mode: TrimMode) -> Self {
+        let text = match mode {
+            TrimMode::None => return self,
+            TrimMode::Start => self.text.trim_start(),
+            TrimMode::End => self.text.trim_end(),
+            TrimMode::Both => self.text.trim(),
+        };
+        if text.len() == self.text.len() {
+            return self;
+        }
+
+        let lead = if text.is_empty() || mode == TrimMode::End {
+            0
+        } else {
+            self.text.len() - self.text.trim_start().len()
+        };
+        let tail = self.text.len() - text.len() - lead;
+        let lead_chars = self.text[..lead].chars().count();
+        let tail_chars = self.text[self.text.len() - tail..].chars().count();
+
+        self.text = text.to_string();
+        self.start += lead;
+        self.end -= tail;
+        if let (Some(start), Some(end)) = (self.char_start, self.char_end) {
+            self.char_start = Some(start + lead_chars);
+            self.char_end = Some(end - tail_chars);
+        }
+        if let (Some(start), Some(end)) = (self.core_start, self.core_end) {
+            let start = start.clamp(self.start, self.end);
+            self.core_start = Some(start);
+            self.core_end = Some(end.clamp(start, self.end));
+        }
+        self
+    }
+}
+
+/// Which ends of a slab's text [`Slab::trimmed`] strips whitespace from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TrimMode {
+    /// Keep the text as is.
+    #[default]
+    None,
+    /// Strip leading whitespace.
+    Start,
+    /// Strip trailing whitespace.
+    End,
+    /// Strip leading and trailing whitespace.
+    Both,
+}
+
+/// Assign core spans to a sequence of possibly overlapping slabs.
+///
+/// `slabs` must be in document order (non-decreasing `start`). Bytes shared by
+/// neighbouring slabs belong to the earlier slab's core; each later core
+/// starts where the covered region so far ends. The resulting cores are
+/// disjoint and together cover every byte that any slab covers. A slab whose
+/// span lies entirely inside earlier slabs gets an empty core.
+///
+/// Core endpoints are taken from slab endpoints, so they are character
+/// boundaries whenever the slab offsets are.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{compute_core_spans, slabs_from_byte_ranges};
+///
+/// let text = "The quick brown fox";
+/// let mut slabs = slabs_from_byte_ranges(text, &[0..11, 8..19]).unwrap();
+/// compute_core_spans(&mut slabs);
+///
+/// assert_eq!(slabs[0].core_span(), Some(0..11));
+/// assert_eq!(slabs[1].core_span(), Some(11..19));
+/// assert_eq!(slabs[1].context_span(), 8..19);
+/// ```
+pub fn compute_core_spans(slabs: &mut [Slab]) {
+    let mut covered_to = 0;
+    for slab in slabs.iter_mut() {
+        let core_start = slab.start.max(covered_to).min(slab.end);
+        slab.core_start = Some(core_start);
+        slab.core_end = Some(slab.end.max(core_start));
+        covered_to = covered_to.max(slab.end);
+    }
 }
 
 /// Create slabs from byte ranges in the source text.
@@ -187,7 +373,32 @@ pub fn slabs_from_char_ranges(source: &str, ranges: &[Range<usize>]) -> Result<V
         .collect()
 }
 
-fn validate_byte_range(source: &str, range: Range<usize>) -> Result<()> {
+/// Check that every slab span is a valid slice of `source`.
+pub(crate) fn validate_slabs(source: &str, slabs: &[Slab]) -> Result<()> {
+    slabs
+        .iter()
+        .try_for_each(|slab| validate_byte_range(source, slab.span()))
+}
+
+/// Borrow every slab whose span is a valid slice of `source`, with character
+/// offsets filled. Slabs with invalid spans are dropped.
+pub(crate) fn borrow_valid_slabs(source: &str, mut slabs: Vec<Slab>) -> Vec<SlabRef<'_>> {
+    slabs.retain(|slab| validate_byte_range(source, slab.span()).is_ok());
+    compute_char_offsets(source, &mut slabs);
+    slabs
+        .iter()
+        .map(|slab| SlabRef {
+            text: &source[slab.span()],
+            start: slab.start,
+            end: slab.end,
+            char_start: slab.char_start,
+            char_end: slab.char_end,
+            index: slab.index,
+        })
+        .collect()
+}
+
+pub(crate) fn validate_byte_range(source: &str, range: Range<usize>) -> Result<()> {
     if range.start > range.end || range.end > source.len() {
         return Err(Error::InvalidByteSpan {
             start: range.start,
@@ -267,6 +478,36 @@ pub fn compute_char_offsets(text: &str, slabs: &mut [Slab]) {
     }
 }
 
+/// Fill character offsets on slabs one at a time, in iteration order.
+///
+/// Unlike [`compute_char_offsets`], this keeps no byte-to-char table. A cursor
+/// walks forward from the previous slab's start, so slabs in document order
+/// cost one pass over the text in total. A slab that starts before the previous
+/// one restarts the count from the beginning of `text`.
+pub(crate) fn with_char_offsets_lazy<'a, I>(
+    text: &'a str,
+    slabs: I,
+) -> impl Iterator<Item = Slab> + 'a
+where
+    I: IntoIterator<Item = Slab>,
+    I::IntoIter: 'a,
+{
+    let mut cursor_byte = 0;
+    let mut cursor_char = 0;
+    slabs.into_iter().map(move |mut slab| {
+        if slab.start < cursor_byte {
+            cursor_byte = 0;
+            cursor_char = 0;
+        }
+        cursor_char += text[cursor_byte..slab.start].chars().count();
+        cursor_byte = slab.start;
+
+        slab.char_start = Some(cursor_char);
+        slab.char_end = Some(cursor_char + text[slab.start..slab.end].chars().count());
+        slab
+    })
+}
+
 impl std::fmt::Display for Slab {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let (Some(cs), Some(ce)) = (self.char_start, self.char_end) {
@@ -293,6 +534,139 @@ impl std::fmt::Display for Slab {
     }
 }
 
+/// A borrowed [`Slab`]: the same span metadata, with text borrowed from the
+/// source string instead of owned.
+///
+/// Use `SlabRef` in high-throughput paths where copying every span's text
+/// would double memory. Convert to an owned [`Slab`] with
+/// [`to_slab`](SlabRef::to_slab) at the point where the span must outlive the
+/// source, for example before storage.
+///
+/// `SlabRef` carries no metadata; metadata is attached to owned slabs.
+///
+/// ```rust
+/// use slabs::SlabRef;
+///
+/// let text = "Hello 日本語 world";
+/// let slab = SlabRef::from_byte_range(text, 6..15, 0).unwrap();
+///
+/// assert_eq!(slab.text, "日本語");
+/// assert!(std::ptr::eq(slab.text.as_ptr(), text[6..].as_ptr()));
+/// assert_eq!(slab.to_slab().char_span(), Some(6..9));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SlabRef<'a> {
+    /// The span text, borrowed from the source string.
+    pub text: &'a str,
+    /// Byte offset where this span starts in the source string.
+    pub start: usize,
+    /// Byte offset where this span ends (exclusive) in the source string.
+    pub end: usize,
+    /// Character offset where this span starts (Unicode scalar values).
+    pub char_start: Option<usize>,
+    /// Character offset where this span ends (exclusive, Unicode scalar values).
+    pub char_end: Option<usize>,
+    /// Zero-based index of this span in the sequence.
+    pub index: usize,
+}
+
+impl<'a> SlabRef<'a> {
+    /// Borrow a byte range of `source`.
+    ///
+    /// The range must be within the source and both endpoints must be UTF-8
+    /// character boundaries. Character offsets are computed automatically.
+    pub fn from_byte_range(source: &'a str, range: Range<usize>, index: usize) -> Result<Self> {
+        validate_byte_range(source, range.clone())?;
+
+        Ok(Self {
+            text: &source[range.clone()],
+            start: range.start,
+            end: range.end,
+            char_start: Some(byte_to_char_offset(source, range.start)),
+            char_end: Some(byte_to_char_offset(source, range.end)),
+            index,
+        })
+    }
+
+    /// Borrow the span of `slab` from `source`.
+    ///
+    /// Character offsets are copied from `slab`. Returns an error if the
+    /// slab's byte span is not a valid slice of `source`.
+    pub fn from_slab(source: &'a str, slab: &Slab) -> Result<Self> {
+        validate_byte_range(source, slab.span())?;
+
+        Ok(Self {
+            text: &source[slab.span()],
+            start: slab.start,
+            end: slab.end,
+            char_start: slab.char_start,
+            char_end: slab.char_end,
+            index: slab.index,
+        })
+    }
+
+    /// Copy the text into an owned [`Slab`] with empty metadata.
+    #[must_use]
+    pub fn to_slab(&self) -> Slab {
+        Slab {
+            text: self.text.to_string(),
+            start: self.start,
+            end: self.end,
+            char_start: self.char_start,
+            char_end: self.char_end,
+            index: self.index,
+            core_start: None,
+            core_end: None,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// The length of this span in bytes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Whether this span is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// The byte span in the source string.
+    #[must_use]
+    pub fn span(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// The character span, if computed.
+    #[must_use]
+    pub fn char_span(&self) -> Option<Range<usize>> {
+        match (self.char_start, self.char_end) {
+            (Some(s), Some(e)) => Some(s..e),
+            _ => None,
+        }
+    }
+}
+
+impl Slab {
+    /// Borrow this slab as a [`SlabRef`] pointing into its own text.
+    ///
+    /// The returned offsets still refer to the original source string.
+    #[must_use]
+    pub fn as_slab_ref(&self) -> SlabRef<'_> {
+        SlabRef {
+            text: &self.text,
+            start: self.start,
+            end: self.end,
+            char_start: self.char_start,
+            char_end: self.char_end,
+            index: self.index,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -335,4 +709,102 @@ mod tests {
         );
         assert_eq!(slabs[2].text, "gamma");
     }
+
+    #[test]
+    fn with_metadata_replaces_existing_key() {
+        let slab = Slab::new("a", 0, 1, 0)
+            .with_metadata("lang", "en")
+            .with_metadata("lang", "de");
+
+        assert_eq!(slab.metadata.len(), 1);
+        assert_eq!(slab.metadata["lang"], "de");
+    }
+
+    #[test]
+    fn lazy_char_offsets_match_batch_computation() {
+        let text = "Hello 日本語 world, café";
+        let spans = [0..6, 6..15, 3..12, 16..21, 0..text.len()];
+        let mut expected: Vec<Slab> = spans
+            .iter()
+            .enumerate()
+            .map(|(i, r)| Slab::new(&text[r.clone()], r.start, r.end, i))
+            .collect();
+        let lazy: Vec<Slab> = with_char_offsets_lazy(text, expected.clone()).collect();
+        compute_char_offsets(text, &mut expected);
+
+        assert_eq!(lazy, expected);
+    }
+
+    #[test]
+    fn core_spans_partition_covered_bytes() {
+        let mut slabs = vec![
+            Slab::new("", 0, 10, 0),
+            Slab::new("", 6, 16, 1),
+            Slab::new("", 8, 12, 2),
+            Slab::new("", 20, 24, 3),
+        ];
+
+        compute_core_spans(&mut slabs);
+
+        assert_eq!(
+            slabs.iter().map(Slab::core_span).collect::<Vec<_>>(),
+            [Some(0..10), Some(10..16), Some(12..12), Some(20..24)]
+        );
+    }
+
+    #[test]
+    fn slab_ref_from_slab_rejects_foreign_source() {
+        let slab = Slab::new("world", 7, 12, 0);
+
+        let err = SlabRef::from_slab("short", &slab).unwrap_err();
+
+        assert!(matches!(err, Error::InvalidByteSpan { end: 12, .. }));
+    }
+
+    #[test]
+    fn slab_ref_round_trips_through_owned_slab() {
+        let text = "alpha beta";
+        let slab = Slab::from_byte_range(text, 6..10, 1).unwrap();
+
+        let borrowed = SlabRef::from_slab(text, &slab).unwrap();
+
+        assert_eq!(borrowed.to_slab(), slab);
+        assert_eq!(slab.as_slab_ref(), borrowed);
+    }
+
+    #[test]
+    fn trimming_moves_char_offsets_and_clamps_core() {
+        let text = "\u{3000}日本 ";
+        let slab = Slab::from_byte_range(text, 0..text.len(), 0)
+            .unwrap()
+            .with_core_span(0..6);
+
+        let trimmed = slab.trimmed(TrimMode::Both);
+
+        assert_eq!(trimmed.text, "日本");
+        assert_eq!(trimmed.span(), 3..9);
+        assert_eq!(trimmed.char_span(), Some(1..3));
+        assert_eq!(trimmed.core_span(), Some(3..6));
+    }
+
+    #[test]
+    fn trim_modes_strip_only_their_side() {
+        let slab = Slab::from_byte_range(" a ", 0..3, 0).unwrap();
+
+        assert_eq!(slab.clone().trimmed(TrimMode::None).span(), 0..3);
+        assert_eq!(slab.clone().trimmed(TrimMode::Start).span(), 1..3);
+        assert_eq!(slab.trimmed(TrimMode::End).span(), 0..2);
+    }
+
+    #[test]
+    fn whitespace_only_slab_trims_to_empty_at_start() {
+        let slab = Slab::from_byte_range("x   y", 1..4, 0).unwrap();
+
+        for mode in [TrimMode::Start, TrimMode::End, TrimMode::Both] {
+            let trimmed = slab.clone().trimmed(mode);
+            assert!(trimmed.is_empty());
+            assert_eq!(trimmed.span(), 1..1);
+            assert_eq!(trimmed.char_span(), Some(1..1));
+        }
+    }
 }
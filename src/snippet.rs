@@ -0,0 +1,136 @@
+//! Search-result snippets cut from slabs.
+
+use std::ops::Range;
+
+use crate::{span, Slab};
+
+/// Marker placed where [`Slab::snippet`] cuts text off.
+pub const ELLIPSIS: &str = "…";
+
+impl Slab {
+    /// Cut a snippet of at most `max_len` bytes of slab text around `matches`.
+    ///
+    /// `matches` are byte ranges in the source, the same coordinates as
+    /// [`span`](Slab::span); parts outside this slab are ignored. The snippet
+    /// covers the first match plus as many following matches as fit, with the
+    /// spare room split evenly before and after. Without matches it is the
+    /// start of the slab. [`ELLIPSIS`] marks each cut end and is not counted
+    /// in `max_len`.
+    ///
+    /// Matches that cut through a character are widened to cover it. Cuts move
+    /// inward to character boundaries, so the snippet never splits a UTF-8
+    /// character but may be shorter than `max_len`. Grapheme clusters are not
+    /// detected. A match longer than `max_len` is truncated.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use slabs::Slab;
+    ///
+    /// let text = "Offsets are byte positions into the source string.";
+    /// let slab = Slab::from_byte_range(text, 0..text.len(), 0).unwrap();
+    ///
+    /// let hit = text.find("source").unwrap();
+    /// assert_eq!(slab.snippet(&[hit..hit + 6], 16), "… the source stri…");
+    /// ```
+    #[must_use]
+    pub fn snippet(&self, matches: &[Range<usize>], max_len: usize) -> String {
+        let text = self.text.as_str();
+        if text.len() <= max_len {
+            return text.to_string();
+        }
+
+        let mut local = matches
+            .iter()
+            .filter(|m| m.start < self.end && m.end > self.start)
+            .map(|m| m.start.max(self.start) - self.start..m.end.min(self.end) - self.start)
+            .map(|m| span::align(text, m))
+            .collect::<Vec<_>>();
+        local.sort_by_key(|m| m.start);
+
+        let (mut start, mut end) = match local.first() {
+            Some(first) => (first.start, first.end.min(first.start + max_len)),
+            None => (0, 0),
+        };
+        for m in local.iter().skip(1) {
+            if m.end.max(end) - start > max_len {
+                break;
+            }
+            end = end.max(m.end);
+        }
+
+        let spare = max_len - (end - start);
+        let before = (spare / 2).min(start);
+        let after = (spare - before).min(text.len() - end);
+        let before = (spare - after).min(start);
+        start -= before;
+        end += after;
+
+        while !text.is_char_boundary(start) {
+            start += 1;
+        }
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        let mut snippet = String::with_capacity(end - start + 2 * ELLIPSIS.len());
+        if start > 0 {
+            snippet.push_str(ELLIPSIS);
+        }
+        snippet.push_str(&text[start..end]);
+        if end < text.len() {
+            snippet.push_str(ELLIPSIS);
+        }
+        snippet
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    fn slab(text: &str, offset: usize) -> Slab {
+        Slab::new(text, offset, offset + text.len(), 0)
+    }
+
+    #[test]
+    fn short_slab_is_returned_whole() {
+        assert_eq!(slab("short", 0).snippet(&[1..2], 10), "short");
+    }
+
+    #[test]
+    fn no_match_keeps_the_start() {
+        assert_eq!(slab("abcdefghij", 0).snippet(&[], 4), "abcd…");
+    }
+
+    #[test]
+    fn match_near_the_end_shifts_room_before_it() {
+        let snippet = slab("abcdefghij", 100).snippet(&[108..110], 4);
+
+        assert_eq!(snippet, "…ghij");
+    }
+
+    #[test]
+    fn matches_that_fit_together_share_one_snippet() {
+        let snippet = slab("0123456789abcdef", 0).snippet(&[9..10, 4..5], 8);
+
+        assert_eq!(snippet, "…3456789a…");
+        assert!(snippet.contains('4') && snippet.contains('9'));
+    }
+
+    #[test]
+    fn cuts_stay_on_char_boundaries() {
+        let snippet = slab("ééééé", 0).snippet(&[4..6], 3);
+
+        assert_eq!(snippet, "…é…");
+    }
+
+    #[test]
+    fn matches_off_a_char_boundary_are_widened() {
+        let slab = Slab::new("ééééé", 0, 10, 0);
+
+        assert_eq!(slab.snippet(&[3..4], 0), "……");
+        assert_eq!(slab.snippet(&[3..4], 2), "…é…");
+    }
+}
@@ -0,0 +1,151 @@
+//! Set operations over byte spans.
+//!
+//! Span sets are slices of `Range<usize>`. Every operation accepts ranges in
+//! any order, possibly overlapping or empty, and returns a coalesced set:
+//! sorted by start, non-empty, and with no two ranges overlapping or touching.
+//!
+//! The operations only ever return endpoints taken from their inputs, so if
+//! every input endpoint is a UTF-8 character boundary of some source string,
+//! so is every output endpoint. Use [`align`] to widen arbitrary ranges to
+//! character boundaries first.
+//!
+//! ```rust
+//! use slabs::span;
+//!
+//! let covered = span::union(&[0..5, 3..8], &[10..12]);
+//! assert_eq!(covered, [0..8, 10..12]);
+//!
+//! let gaps = span::subtract(&[0..12], &covered);
+//! assert_eq!(gaps, [8..10]);
+//! ```
+
+use std::ops::Range;
+
+/// Sort and merge overlapping or adjacent ranges, dropping empty ones.
+#[must_use]
+pub fn coalesce(ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut sorted: Vec<Range<usize>> =
+        ranges.iter().filter(|r| r.start < r.end).cloned().collect();
+    sorted.sort_by_key(|r| (r.start, r.end));
+
+    let mut out: Vec<Range<usize>> = Vec::with_capacity(sorted.len());
+    for range in sorted {
+        match out.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => out.push(range),
+        }
+    }
+    out
+}
+
+/// Bytes covered by `a` or `b`.
+#[must_use]
+pub fn union(a: &[Range<usize>], b: &[Range<usize>]) -> Vec<Range<usize>> {
+    let all: Vec<Range<usize>> = a.iter().chain(b).cloned().collect();
+    coalesce(&all)
+}
+
+/// Bytes covered by both `a` and `b`.
+#[must_use]
+pub fn intersection(a: &[Range<usize>], b: &[Range<usize>]) -> Vec<Range<usize>> {
+    let a = coalesce(a);
+    let b = coalesce(b);
+
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let start = a[i].start.max(b[j].start);
+        let end = a[i].end.min(b[j].end);
+        if start < end {
+            out.push(start..end);
+        }
+        if a[i].end < b[j].end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    out
+}
+
+/// Bytes covered by `a` but not by `b`.
+#[must_use]
+pub fn subtract(a: &[Range<usize>], b: &[Range<usize>]) -> Vec<Range<usize>> {
+    let b = coalesce(b);
+
+    let mut out = Vec::new();
+    for range in coalesce(a) {
+        let mut start = range.start;
+        for cut in b
+            .iter()
+            .filter(|cut| cut.start < range.end && cut.end > range.start)
+        {
+            if cut.start > start {
+                out.push(start..cut.start);
+            }
+            start = start.max(cut.end);
+        }
+        if start < range.end {
+            out.push(start..range.end);
+        }
+    }
+    out
+}
+
+/// Total number of bytes covered by `ranges`, counting overlaps once.
+#[must_use]
+pub fn covered_len(ranges: &[Range<usize>]) -> usize {
+    coalesce(ranges).iter().map(|r| r.end - r.start).sum()
+}
+
+/// Widen `range` to the nearest UTF-8 character boundaries in `source`.
+///
+/// The start moves backward and the end moves forward until each falls on a
+/// character boundary. Both endpoints are first clamped to `source.len()`.
+#[must_use]
+pub fn align(source: &str, range: Range<usize>) -> Range<usize> {
+    let mut start = range.start.min(source.len());
+    let mut end = range.end.min(source.len()).max(start);
+    while !source.is_char_boundary(start) {
+        start -= 1;
+    }
+    while !source.is_char_boundary(end) {
+        end += 1;
+    }
+    start..end
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coalesce_merges_overlapping_and_touching_ranges() {
+        assert_eq!(coalesce(&[6..9, 0..3, 3..5, 2..4, 7..7]), [0..5, 6..9]);
+    }
+
+    #[test]
+    fn intersection_keeps_only_shared_bytes() {
+        assert_eq!(intersection(&[0..10, 20..30], &[5..25]), [5..10, 20..25]);
+        assert!(intersection(&[0..3], &[3..6]).is_empty());
+    }
+
+    #[test]
+    fn subtract_splits_around_holes() {
+        assert_eq!(subtract(&[0..10], &[2..3, 5..7, 9..12]), [0..2, 3..5, 7..9]);
+        assert!(subtract(&[2..4], &[0..10]).is_empty());
+    }
+
+    #[test]
+    fn covered_len_counts_overlap_once() {
+        assert_eq!(covered_len(&[0..5, 3..8, 10..11]), 9);
+    }
+
+    #[test]
+    fn align_widens_to_character_boundaries() {
+        let text = "a日b";
+        assert_eq!(align(text, 2..3), 1..4);
+        assert_eq!(align(text, 0..99), 0..5);
+    }
+}
@@ -0,0 +1,183 @@
+//! Rendering slab text for embedding.
+
+use crate::{Error, Result, Slab};
+
+/// A text template applied to slabs at embedding or export time.
+///
+/// Templates add context such as a heading path or source reference to the
+/// text an embedder sees, without touching [`Slab::text`] or its offsets.
+///
+/// Placeholders are written in braces:
+///
+/// - `{text}`, `{index}`, `{start}`, `{end}`: slab fields.
+/// - `{char_start}`, `{char_end}`: character offsets, empty when unset.
+/// - Any other name: the metadata value under that key, empty when absent.
+///
+/// Write `{{` and `}}` for literal braces.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{Slab, SlabTemplate};
+///
+/// let template =
+///     SlabTemplate::parse("{heading_path}\n\n{text}\n\nSource: {doc_id}#{index}").unwrap();
+/// let slab = Slab::new("Run make.", 40, 49, 3)
+///     .with_metadata("heading_path", "Install > Linux")
+///     .with_metadata("doc_id", "guide.md");
+///
+/// assert_eq!(
+///     template.render(&slab),
+///     "Install > Linux\n\nRun make.\n\nSource: guide.md#3"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlabTemplate {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Field(String),
+}
+
+impl SlabTemplate {
+    /// Parse a template string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidTemplate`] for an unclosed `{`, an unmatched
+    /// `}`, or an empty placeholder.
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.char_indices().peekable();
+
+        while let Some((pos, c)) = chars.next() {
+            match c {
+                '{' if chars.peek().map(|&(_, next)| next) == Some('{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek().map(|&(_, next)| next) == Some('}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let rest = &template[pos + 1..];
+                    let Some(len) = rest.find('}') else {
+                        return Err(Error::InvalidTemplate {
+                            offset: pos,
+                            reason: "unclosed placeholder",
+                        });
+                    };
+                    let name = rest[..len].trim();
+                    if name.is_empty() || name.contains('{') {
+                        return Err(Error::InvalidTemplate {
+                            offset: pos,
+                            reason: "empty or nested placeholder",
+                        });
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Field(name.to_string()));
+                    // Skip the placeholder body and its closing brace.
+                    while chars.next_if(|&(p, _)| p <= pos + 1 + len).is_some() {}
+                }
+                '}' => {
+                    return Err(Error::InvalidTemplate {
+                        offset: pos,
+                        reason: "unmatched closing brace",
+                    });
+                }
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(Self { parts })
+    }
+
+    /// Render `slab` through this template.
+    #[must_use]
+    pub fn render(&self, slab: &Slab) -> String {
+        let mut out = String::with_capacity(slab.text.len() + 64);
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => out.push_str(text),
+                Part::Field(name) => match name.as_str() {
+                    "text" => out.push_str(&slab.text),
+                    "index" => out.push_str(&slab.index.to_string()),
+                    "start" => out.push_str(&slab.start.to_string()),
+                    "end" => out.push_str(&slab.end.to_string()),
+                    "char_start" => push_optional(&mut out, slab.char_start),
+                    "char_end" => push_optional(&mut out, slab.char_end),
+                    key => {
+                        if let Some(value) = slab.metadata.get(key) {
+                            out.push_str(value);
+                        }
+                    }
+                },
+            }
+        }
+        out
+    }
+
+    /// Render every slab, in order.
+    #[must_use]
+    pub fn render_all(&self, slabs: &[Slab]) -> Vec<String> {
+        slabs.iter().map(|slab| self.render(slab)).collect()
+    }
+}
+
+fn push_optional(out: &mut String, value: Option<usize>) {
+    if let Some(value) = value {
+        out.push_str(&value.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaped_braces_render_literally() {
+        let template = SlabTemplate::parse("{{{index}}}").unwrap();
+
+        assert_eq!(template.render(&Slab::new("x", 0, 1, 7)), "{7}");
+    }
+
+    #[test]
+    fn missing_metadata_and_offsets_render_empty() {
+        let template = SlabTemplate::parse("[{section}|{char_start}] {text}").unwrap();
+
+        assert_eq!(template.render(&Slab::new("x", 0, 1, 0)), "[|] x");
+    }
+
+    #[test]
+    fn malformed_templates_report_offsets() {
+        let unclosed = SlabTemplate::parse("ab {text").unwrap_err();
+        let stray = SlabTemplate::parse("ab }").unwrap_err();
+        let empty = SlabTemplate::parse("{ }").unwrap_err();
+
+        assert!(matches!(unclosed, Error::InvalidTemplate { offset: 3, .. }));
+        assert!(matches!(stray, Error::InvalidTemplate { offset: 3, .. }));
+        assert!(matches!(empty, Error::InvalidTemplate { offset: 0, .. }));
+    }
+
+    #[test]
+    fn rendering_leaves_slab_untouched() {
+        let template = SlabTemplate::parse("prefix: {text}").unwrap();
+        let slab = Slab::from_byte_range("hello", 0..5, 0).unwrap();
+
+        let rendered = template.render(&slab);
+
+        assert_eq!(rendered, "prefix: hello");
+        assert_eq!(slab.text, "hello");
+        assert_eq!(slab.char_span(), Some(0..5));
+    }
+}
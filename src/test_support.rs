@@ -0,0 +1,21 @@
+//! Fixtures shared by unit tests.
+
+use crate::{Chunker, Slab};
+
+/// One slab per line, newline included, the way a thin line-splitting
+/// adapter would behave.
+pub(crate) struct Lines;
+
+impl Chunker for Lines {
+    fn chunk_bytes(&self, text: &str) -> Vec<Slab> {
+        let mut start = 0;
+        text.split_inclusive('\n')
+            .enumerate()
+            .map(|(i, line)| {
+                let slab = Slab::new(line, start, start + line.len(), i);
+                start += line.len();
+                slab
+            })
+            .collect()
+    }
+}
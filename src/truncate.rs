@@ -0,0 +1,132 @@
+//! Truncating retrieved text to model limits.
+
+/// Truncate `text` to at most `max_bytes` bytes without splitting a character.
+///
+/// The cut moves back to the nearest UTF-8 character boundary, so the result
+/// may be shorter than `max_bytes`. With the `unicode-segmentation` feature
+/// it moves back to the nearest extended grapheme cluster boundary instead,
+/// so a base character keeps its combining marks and emoji sequences stay
+/// whole. Without the feature a cut can separate a base character from a
+/// following combining mark.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::truncate_to_bytes;
+///
+/// assert_eq!(truncate_to_bytes("héllo", 2), "h");
+/// assert_eq!(truncate_to_bytes("héllo", 3), "hé");
+/// assert_eq!(truncate_to_bytes("héllo", 64), "héllo");
+/// ```
+#[must_use]
+pub fn truncate_to_bytes(text: &str, max_bytes: usize) -> &str {
+    if max_bytes >= text.len() {
+        return text;
+    }
+    &text[..floor_boundary(text, max_bytes)]
+}
+
+/// The last grapheme cluster boundary at or before `index`.
+#[cfg(feature = "unicode-segmentation")]
+pub(crate) fn floor_boundary(text: &str, index: usize) -> usize {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    if index >= text.len() {
+        return text.len();
+    }
+    text.grapheme_indices(true)
+        .map(|(start, _)| start)
+        .take_while(|&start| start <= index)
+        .last()
+        .unwrap_or(0)
+}
+
+/// The last character boundary at or before `index`.
+#[cfg(not(feature = "unicode-segmentation"))]
+pub(crate) fn floor_boundary(text: &str, index: usize) -> usize {
+    if index >= text.len() {
+        return text.len();
+    }
+    let mut end = index;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    end
+}
+
+/// Truncate `text` to its first `max_tokens` tokens.
+///
+/// `token_offsets` are byte spans reported by a tokenizer for `text`, in token
+/// order, as for [`slabs_from_token_windows`](crate::slabs_from_token_windows).
+/// The result ends at the end of token `max_tokens - 1`, so trailing text
+/// between that token and the next is dropped. With fewer tokens than
+/// `max_tokens`, `text` is returned whole. An offset past the end of `text`
+/// or off a character boundary is clamped with [`truncate_to_bytes`].
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::truncate_to_tokens;
+///
+/// let text = "one two three";
+/// let tokens = [(0, 3), (4, 7), (8, 13)];
+///
+/// assert_eq!(truncate_to_tokens(text, &tokens, 2), "one two");
+/// assert_eq!(truncate_to_tokens(text, &tokens, 0), "");
+/// ```
+#[must_use]
+pub fn truncate_to_tokens<'a>(
+    text: &'a str,
+    token_offsets: &[(usize, usize)],
+    max_tokens: usize,
+) -> &'a str {
+    if max_tokens >= token_offsets.len() {
+        return text;
+    }
+    let end = match max_tokens.checked_sub(1) {
+        Some(last) => token_offsets[last].1,
+        None => 0,
+    };
+    truncate_to_bytes(text, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_cut_inside_a_four_byte_char_backs_off() {
+        let text = "a🦀b";
+
+        assert_eq!(truncate_to_bytes(text, 1), "a");
+        assert_eq!(truncate_to_bytes(text, 4), "a");
+        assert_eq!(truncate_to_bytes(text, 5), "a🦀");
+        assert_eq!(truncate_to_bytes(text, 0), "");
+    }
+
+    #[test]
+    fn fewer_tokens_than_limit_keep_trailing_text() {
+        let text = "one two ";
+
+        assert_eq!(truncate_to_tokens(text, &[(0, 3), (4, 7)], 2), "one two ");
+        assert_eq!(truncate_to_tokens(text, &[(0, 3), (4, 7)], 1), "one");
+    }
+
+    #[test]
+    fn token_offset_off_a_char_boundary_is_clamped() {
+        let text = "日本語";
+
+        assert_eq!(truncate_to_tokens(text, &[(0, 4), (4, 9)], 1), "日");
+    }
+
+    #[cfg(feature = "unicode-segmentation")]
+    #[test]
+    fn cuts_keep_grapheme_clusters_whole() {
+        // "e" followed by U+0301 COMBINING ACUTE ACCENT.
+        let text = "cafe\u{301} ok";
+
+        assert_eq!(truncate_to_bytes(text, 5), "caf");
+        assert_eq!(truncate_to_bytes(text, 6), "cafe\u{301}");
+        assert_eq!(truncate_to_bytes("👍🏽!", 4), "");
+    }
+}
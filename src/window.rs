@@ -0,0 +1,296 @@
+//! Windows over sequences of slabs and tokens.
+
+use crate::slab::validate_byte_range;
+use crate::{Error, Result, Slab};
+
+/// Metadata key holding the window text written by [`attach_sentence_windows`].
+pub const WINDOW_KEY: &str = "window";
+/// Metadata key holding the window start byte offset.
+pub const WINDOW_START_KEY: &str = "window_start";
+/// Metadata key holding the window end byte offset (exclusive).
+pub const WINDOW_END_KEY: &str = "window_end";
+/// Metadata key holding the first token index of a token window.
+pub const TOKEN_START_KEY: &str = "token_start";
+/// Metadata key holding the token index one past the end of a token window.
+pub const TOKEN_END_KEY: &str = "token_end";
+/// Metadata key holding the position of the first slab merged into a window.
+pub const SLAB_START_KEY: &str = "slab_start";
+/// Metadata key holding the position one past the last slab merged into a
+/// window.
+pub const SLAB_END_KEY: &str = "slab_end";
+
+/// Attach a window of `k` neighbouring slabs on each side to every slab.
+///
+/// This is the sentence-window retrieval pattern: embed and index the small
+/// slabs, then hand the wider window to the reader. `slabs` is taken in
+/// document order, typically one slab per sentence. For slab `i`, the window
+/// runs from the start of slab `i - k` to the end of slab `i + k` (clamped to
+/// the sequence) and includes any gap text between them.
+///
+/// The window text and its byte span are stored under [`WINDOW_KEY`],
+/// [`WINDOW_START_KEY`], and [`WINDOW_END_KEY`]. Slab text and offsets are
+/// unchanged.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidByteSpan`] or [`Error::NonCharBoundary`] if a
+/// window does not describe a valid slice of `source`. All earlier slabs keep
+/// the metadata already written.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{attach_sentence_windows, slabs_from_byte_ranges, WINDOW_KEY};
+///
+/// let text = "One. Two. Three.";
+/// let mut slabs = slabs_from_byte_ranges(text, &[0..4, 5..9, 10..16]).unwrap();
+/// attach_sentence_windows(text, &mut slabs, 1).unwrap();
+///
+/// assert_eq!(slabs[0].metadata[WINDOW_KEY], "One. Two.");
+/// assert_eq!(slabs[1].metadata[WINDOW_KEY], "One. Two. Three.");
+/// ```
+pub fn attach_sentence_windows(source: &str, slabs: &mut [Slab], k: usize) -> Result<()> {
+    let spans: Vec<(usize, usize)> = slabs.iter().map(|slab| (slab.start, slab.end)).collect();
+
+    for (i, slab) in slabs.iter_mut().enumerate() {
+        let first = i.saturating_sub(k);
+        let last = (i + k).min(spans.len() - 1);
+        let start = spans[first].0.min(slab.start);
+        let end = spans[last].1.max(slab.end);
+
+        validate_byte_range(source, start..end)?;
+        slab.metadata
+            .insert(WINDOW_KEY.to_string(), source[start..end].to_string());
+        slab.metadata
+            .insert(WINDOW_START_KEY.to_string(), start.to_string());
+        slab.metadata
+            .insert(WINDOW_END_KEY.to_string(), end.to_string());
+    }
+
+    Ok(())
+}
+
+/// Build slabs covering windows of `window` tokens, advancing by `stride`.
+///
+/// `token_offsets` are byte spans reported by a tokenizer, in token order.
+/// Window `k` covers tokens `k * stride .. k * stride + window`, clamped to the
+/// token count, and the slab spans from the first token's start to the last
+/// token's end. Windows stop once one reaches the final token, so only the
+/// last window can hold fewer than `window` tokens. Token indices are stored
+/// under [`TOKEN_START_KEY`] and [`TOKEN_END_KEY`].
+///
+/// This gives token-exact windows for evaluation pipelines (perplexity,
+/// retrieval over fixed token budgets) without a tokenizer dependency here.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidWindow`] if `window` or `stride` is zero, and a
+/// span error if a window's byte range is not a valid slice of `source`.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{slabs_from_token_windows, TOKEN_START_KEY};
+///
+/// let text = "a b c d e";
+/// let tokens = [(0, 1), (2, 3), (4, 5), (6, 7), (8, 9)];
+/// let slabs = slabs_from_token_windows(text, &tokens, 3, 2).unwrap();
+///
+/// let texts: Vec<&str> = slabs.iter().map(|s| s.text.as_str()).collect();
+/// assert_eq!(texts, ["a b c", "c d e"]);
+/// assert_eq!(slabs[1].metadata[TOKEN_START_KEY], "2");
+/// ```
+pub fn slabs_from_token_windows(
+    source: &str,
+    token_offsets: &[(usize, usize)],
+    window: usize,
+    stride: usize,
+) -> Result<Vec<Slab>> {
+    windows_over_spans(
+        source,
+        token_offsets,
+        window,
+        stride,
+        (TOKEN_START_KEY, TOKEN_END_KEY),
+    )
+}
+
+/// Merge consecutive slabs into windows of `window` slabs, advancing by
+/// `stride`.
+///
+/// This is the sliding sentence-window pattern: with one slab per sentence,
+/// `window = 5, stride = 2` yields overlapping five-sentence slabs starting at
+/// every other sentence. Each window slab spans from the first member's start
+/// to the last member's end in `source`, including gap text. Positions of the
+/// first and one-past-last member in `slabs` are stored under
+/// [`SLAB_START_KEY`] and [`SLAB_END_KEY`]. Window semantics match
+/// [`slabs_from_token_windows`].
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidWindow`] if `window` or `stride` is zero, and a
+/// span error if a window's byte range is not a valid slice of `source`.
+///
+/// # Example
+///
+/// ```rust
+/// use slabs::{slab_windows, slabs_from_byte_ranges};
+///
+/// let text = "A. B. C. D.";
+/// let sentences = slabs_from_byte_ranges(text, &[0..2, 3..5, 6..8, 9..11]).unwrap();
+///
+/// let windows = slab_windows(text, &sentences, 3, 1).unwrap();
+/// let texts: Vec<&str> = windows.iter().map(|s| s.text.as_str()).collect();
+/// assert_eq!(texts, ["A. B. C.", "B. C. D."]);
+/// ```
+pub fn slab_windows(
+    source: &str,
+    slabs: &[Slab],
+    window: usize,
+    stride: usize,
+) -> Result<Vec<Slab>> {
+    let spans: Vec<(usize, usize)> = slabs.iter().map(|slab| (slab.start, slab.end)).collect();
+    windows_over_spans(
+        source,
+        &spans,
+        window,
+        stride,
+        (SLAB_START_KEY, SLAB_END_KEY),
+    )
+}
+
+fn windows_over_spans(
+    source: &str,
+    spans: &[(usize, usize)],
+    window: usize,
+    stride: usize,
+    (start_key, end_key): (&str, &str),
+) -> Result<Vec<Slab>> {
+    if window == 0 || stride == 0 {
+        return Err(Error::InvalidWindow { window, stride });
+    }
+
+    let n = spans.len();
+    let mut slabs = Vec::with_capacity(n.saturating_sub(window).div_ceil(stride) + 1);
+    let mut first = 0;
+    while first < n {
+        let last = (first + window).min(n);
+        let start = spans[first..last]
+            .iter()
+            .map(|&(start, _)| start)
+            .min()
+            .unwrap_or_default();
+        let end = spans[first..last]
+            .iter()
+            .map(|&(_, end)| end)
+            .max()
+            .unwrap_or(start);
+
+        let slab = Slab::from_byte_range(source, start..end, slabs.len())?
+            .with_metadata(start_key, first.to_string())
+            .with_metadata(end_key, last.to_string());
+        slabs.push(slab);
+
+        if last == n {
+            break;
+        }
+        first += stride;
+    }
+
+    Ok(slabs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slabs_from_byte_ranges;
+
+    #[test]
+    fn windows_clamp_at_sequence_edges() {
+        let text = "A. B. C. D.";
+        let mut slabs = slabs_from_byte_ranges(text, &[0..2, 3..5, 6..8, 9..11]).unwrap();
+
+        attach_sentence_windows(text, &mut slabs, 2).unwrap();
+
+        assert_eq!(slabs[0].metadata[WINDOW_KEY], "A. B. C.");
+        assert_eq!(slabs[3].metadata[WINDOW_KEY], "B. C. D.");
+        assert_eq!(slabs[3].metadata[WINDOW_START_KEY], "3");
+        assert_eq!(slabs[3].metadata[WINDOW_END_KEY], "11");
+        assert_eq!(slabs[3].text, "D.");
+    }
+
+    #[test]
+    fn zero_window_is_the_slab_itself() {
+        let text = "A. B.";
+        let mut slabs = slabs_from_byte_ranges(text, &[0..2, 3..5]).unwrap();
+
+        attach_sentence_windows(text, &mut slabs, 0).unwrap();
+
+        assert_eq!(slabs[1].metadata[WINDOW_KEY], "B.");
+    }
+
+    #[test]
+    fn window_outside_source_is_an_error() {
+        let mut slabs = vec![Slab::new("far", 10, 13, 0)];
+
+        let err = attach_sentence_windows("short", &mut slabs, 1).unwrap_err();
+
+        assert!(matches!(err, Error::InvalidByteSpan { end: 13, .. }));
+    }
+
+    #[test]
+    fn token_windows_cover_every_token_once_without_overlap() {
+        let text = "ab cd ef gh";
+        let tokens = [(0, 2), (3, 5), (6, 8), (9, 11)];
+
+        let slabs = slabs_from_token_windows(text, &tokens, 2, 2).unwrap();
+
+        assert_eq!(
+            slabs.iter().map(|s| s.text.as_str()).collect::<Vec<_>>(),
+            ["ab cd", "ef gh"]
+        );
+        assert_eq!(slabs.iter().map(|s| s.index).collect::<Vec<_>>(), [0, 1]);
+    }
+
+    #[test]
+    fn short_documents_yield_one_partial_window() {
+        let slabs = slabs_from_token_windows("ab cd", &[(0, 2), (3, 5)], 8, 4).unwrap();
+
+        assert_eq!(slabs.len(), 1);
+        assert_eq!(slabs[0].metadata[TOKEN_END_KEY], "2");
+    }
+
+    #[test]
+    fn zero_stride_is_rejected() {
+        let err = slabs_from_token_windows("ab", &[(0, 2)], 1, 0).unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::InvalidWindow {
+                window: 1,
+                stride: 0
+            }
+        ));
+    }
+
+    #[test]
+    fn slab_windows_overlap_by_window_minus_stride() {
+        let text = "A. B. C. D. E.";
+        let sentences = slabs_from_byte_ranges(text, &[0..2, 3..5, 6..8, 9..11, 12..14]).unwrap();
+
+        let windows = slab_windows(text, &sentences, 3, 2).unwrap();
+
+        assert_eq!(
+            windows.iter().map(|s| s.text.as_str()).collect::<Vec<_>>(),
+            ["A. B. C.", "C. D. E."]
+        );
+        assert_eq!(windows[1].metadata[SLAB_START_KEY], "2");
+        assert_eq!(windows[1].metadata[SLAB_END_KEY], "5");
+        assert_eq!(windows[1].char_span(), Some(6..14));
+    }
+
+    #[test]
+    fn no_tokens_yield_no_windows() {
+        assert!(slabs_from_token_windows("", &[], 4, 2).unwrap().is_empty());
+    }
+}
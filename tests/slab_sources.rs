@@ -0,0 +1,120 @@
+//! Default `SlabSource` and `Chunker` methods over a user-defined adapter.
+//!
+//! Adapters implement only the byte-offset method. Every derived path (owned,
+//! borrowed, lazy, and through `dyn SlabSource`) must agree on text, byte
+//! offsets, and character offsets.
+
+use slabs::{Chunker, Error, Slab, SlabSource};
+
+/// Splits on ASCII spaces, the way a thin regex or parser adapter would.
+struct Words;
+
+impl Chunker for Words {
+    fn chunk_bytes(&self, text: &str) -> Vec<Slab> {
+        let mut out = Vec::new();
+        let mut start = 0;
+        for (i, part) in text.split(' ').enumerate() {
+            out.push(Slab::new(part, start, start + part.len(), i));
+            start += part.len() + 1;
+        }
+        out
+    }
+}
+
+const TEXT: &str = "naïve café 日本語 rocket🚀 done";
+
+#[test]
+fn lazy_iteration_matches_eager_slabs() {
+    let eager = Words.slabs(TEXT);
+    let lazy: Vec<Slab> = Words.slabs_iter(TEXT).collect();
+
+    assert_eq!(lazy, eager);
+    assert_eq!(eager[2].char_span(), Some(11..14));
+}
+
+#[test]
+fn lazy_iteration_can_stop_early() {
+    let first_two: Vec<String> = Words.chunk_iter(TEXT).take(2).map(|s| s.text).collect();
+
+    assert_eq!(first_two, ["naïve", "café"]);
+}
+
+#[test]
+fn borrowed_slabs_point_into_source() {
+    let refs = Words.slab_refs(TEXT);
+
+    assert_eq!(refs.len(), 5);
+    for slab in &refs {
+        assert_eq!(&TEXT[slab.span()], slab.text);
+        assert!(std::ptr::eq(
+            slab.text.as_ptr(),
+            TEXT[slab.start..].as_ptr()
+        ));
+    }
+}
+
+#[test]
+fn sources_remain_object_safe() {
+    let source: Box<dyn SlabSource> = Box::new(Words);
+
+    assert_eq!(source.slabs(TEXT), Words.chunk(TEXT));
+}
+
+/// Reports a span that ends inside a multi-byte character.
+struct Broken;
+
+impl SlabSource for Broken {
+    fn slab_bytes(&self, _text: &str) -> Vec<Slab> {
+        vec![Slab::new("", 0, 2, 0)]
+    }
+}
+
+#[test]
+fn try_slabs_accepts_valid_spans() {
+    assert_eq!(Words.try_slabs(TEXT).unwrap(), Words.slabs(TEXT));
+    assert_eq!(Words.try_chunk(TEXT).unwrap(), Words.chunk(TEXT));
+}
+
+#[test]
+fn try_slabs_rejects_spans_off_char_boundaries() {
+    let text = "日本";
+    let source: &dyn SlabSource = &Broken;
+
+    let err = source.try_slabs(text).unwrap_err();
+
+    assert!(matches!(err, Error::NonCharBoundary { offset: 2 }));
+}
+
+/// Reports a span past the end of its input.
+struct Overlong;
+
+impl Chunker for Overlong {
+    fn chunk_bytes(&self, _text: &str) -> Vec<Slab> {
+        vec![Slab::new("", 0, 10, 0)]
+    }
+}
+
+#[test]
+fn try_slabs_rejects_spans_past_the_end() {
+    let err = Overlong.try_slabs("abc").unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::InvalidByteSpan {
+            start: 0,
+            end: 10,
+            len: 3
+        }
+    ));
+    assert!(matches!(
+        Overlong.try_chunk("abc"),
+        Err(Error::InvalidByteSpan { .. })
+    ));
+}
+
+#[test]
+fn borrowed_paths_skip_invalid_spans() {
+    assert!(Broken.slab_refs("日本").is_empty());
+    assert!(Overlong.slab_refs("abc").is_empty());
+    assert!(Overlong.chunk_borrowed("abc").is_empty());
+}